@@ -0,0 +1,5 @@
+use fungible_token_io::FTokenMetadata;
+
+fn main() {
+    gear_wasm_builder::build_with_metadata::<FTokenMetadata>();
+}
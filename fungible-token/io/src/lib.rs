@@ -0,0 +1,88 @@
+#![no_std]
+
+use gmeta::{In, InOut, Metadata, Out};
+use gstd::{prelude::*, ActorId};
+
+pub struct FTokenMetadata;
+
+impl Metadata for FTokenMetadata {
+    type Init = In<InitFToken>;
+    type Handle = InOut<FTAction, Result<FTEvent, FTError>>;
+    type Others = ();
+    type Reply = ();
+    type Signal = ();
+    type State = Out<IoFToken>;
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct InitFToken {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum FTAction {
+    /// Mints `amount` tokens to the sender. Allowed only for the admin.
+    Mint(u128),
+    /// Burns `amount` tokens of the sender.
+    Burn(u128),
+    /// Transfers tokens of `from`, the sender must be `from` or be approved by it.
+    Transfer {
+        from: ActorId,
+        to: ActorId,
+        amount: u128,
+    },
+    /// Allows `to` to transfer up to `amount` tokens of the sender.
+    Approve {
+        to: ActorId,
+        amount: u128,
+    },
+    TotalSupply,
+    BalanceOf(ActorId),
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum FTEvent {
+    Transfer {
+        from: ActorId,
+        to: ActorId,
+        amount: u128,
+    },
+    Approve {
+        from: ActorId,
+        to: ActorId,
+        amount: u128,
+    },
+    TotalSupply(u128),
+    Balance(u128),
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum FTError {
+    NotAdmin,
+    ZeroAmount,
+    InsufficientBalance,
+    InsufficientAllowance,
+}
+
+#[derive(Debug, Clone, Default, Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct IoFToken {
+    pub admin: ActorId,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub total_supply: u128,
+    pub balances: Vec<(ActorId, u128)>,
+    pub allowances: Vec<((ActorId, ActorId), u128)>,
+}
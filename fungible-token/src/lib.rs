@@ -0,0 +1,210 @@
+#![no_std]
+
+use fungible_token_io::*;
+use gstd::{collections::HashMap, msg, prelude::*, ActorId};
+
+#[derive(Debug, Clone, Default)]
+struct FToken {
+    admin: ActorId,
+    name: String,
+    symbol: String,
+    decimals: u8,
+    total_supply: u128,
+    balances: HashMap<ActorId, u128>,
+    allowances: HashMap<(ActorId, ActorId), u128>,
+}
+
+static mut FTOKEN: Option<FToken> = None;
+
+impl FToken {
+    /// Mints tokens to the admin
+    /// Arguments:
+    /// `amount`: the number of tokens
+    fn mint(&mut self, amount: u128) -> Result<FTEvent, FTError> {
+        let source = msg::source();
+
+        if source != self.admin {
+            return Err(FTError::NotAdmin);
+        }
+
+        if amount == 0 {
+            return Err(FTError::ZeroAmount);
+        }
+
+        self.balances
+            .entry(source)
+            .and_modify(|balance| *balance = balance.saturating_add(amount))
+            .or_insert(amount);
+        self.total_supply = self.total_supply.saturating_add(amount);
+
+        Ok(FTEvent::Transfer {
+            from: ActorId::zero(),
+            to: source,
+            amount,
+        })
+    }
+
+    /// Burns tokens of the sender
+    /// Arguments:
+    /// `amount`: the number of tokens
+    fn burn(&mut self, amount: u128) -> Result<FTEvent, FTError> {
+        let source = msg::source();
+
+        if amount == 0 {
+            return Err(FTError::ZeroAmount);
+        }
+
+        self.decrease_balance(&source, amount)?;
+        self.total_supply = self.total_supply.saturating_sub(amount);
+
+        Ok(FTEvent::Transfer {
+            from: source,
+            to: ActorId::zero(),
+            amount,
+        })
+    }
+
+    /// Transfers tokens, spending the allowance if the sender isn't `from`
+    /// Arguments:
+    /// `from`: the sender account
+    /// `to`: the recipient account
+    /// `amount`: the number of tokens
+    fn transfer(&mut self, from: ActorId, to: ActorId, amount: u128) -> Result<FTEvent, FTError> {
+        let source = msg::source();
+
+        if amount == 0 {
+            return Err(FTError::ZeroAmount);
+        }
+
+        if self.balances.get(&from).copied().unwrap_or_default() < amount {
+            return Err(FTError::InsufficientBalance);
+        }
+
+        if source != from {
+            let allowance = self
+                .allowances
+                .get_mut(&(from, source))
+                .ok_or(FTError::InsufficientAllowance)?;
+
+            if *allowance < amount {
+                return Err(FTError::InsufficientAllowance);
+            }
+
+            *allowance -= amount;
+        }
+
+        self.decrease_balance(&from, amount)?;
+        self.balances
+            .entry(to)
+            .and_modify(|balance| *balance = balance.saturating_add(amount))
+            .or_insert(amount);
+
+        Ok(FTEvent::Transfer { from, to, amount })
+    }
+
+    /// Sets the allowance of `to` over tokens of the sender
+    /// Arguments:
+    /// `to`: the approved account
+    /// `amount`: the number of tokens
+    fn approve(&mut self, to: ActorId, amount: u128) -> FTEvent {
+        let from = msg::source();
+
+        self.allowances.insert((from, to), amount);
+
+        FTEvent::Approve { from, to, amount }
+    }
+
+    fn decrease_balance(&mut self, account: &ActorId, amount: u128) -> Result<(), FTError> {
+        let balance = self
+            .balances
+            .get_mut(account)
+            .ok_or(FTError::InsufficientBalance)?;
+
+        if *balance < amount {
+            return Err(FTError::InsufficientBalance);
+        }
+
+        *balance -= amount;
+
+        Ok(())
+    }
+}
+
+#[no_mangle]
+extern fn handle() {
+    let ftoken = unsafe { FTOKEN.get_or_insert(FToken::default()) };
+
+    let action: FTAction = msg::load().expect("Could not load Action");
+
+    let result = match action {
+        FTAction::Mint(amount) => ftoken.mint(amount),
+        FTAction::Burn(amount) => ftoken.burn(amount),
+        FTAction::Transfer { from, to, amount } => ftoken.transfer(from, to, amount),
+        FTAction::Approve { to, amount } => Ok(ftoken.approve(to, amount)),
+        FTAction::TotalSupply => Ok(FTEvent::TotalSupply(ftoken.total_supply)),
+        FTAction::BalanceOf(account) => Ok(FTEvent::Balance(
+            ftoken.balances.get(&account).copied().unwrap_or_default(),
+        )),
+    };
+
+    msg::reply(result, 0).expect("Failed to encode or reply with `Result<FTEvent, FTError>`");
+}
+
+#[no_mangle]
+extern fn init() {
+    let InitFToken {
+        name,
+        symbol,
+        decimals,
+    } = msg::load().expect("Unable to decode InitFToken");
+
+    unsafe {
+        FTOKEN = Some(FToken {
+            admin: msg::source(),
+            name,
+            symbol,
+            decimals,
+            ..Default::default()
+        })
+    };
+}
+
+#[no_mangle]
+extern fn state() {
+    let ftoken = unsafe { FTOKEN.take().expect("Unexpected error in taking state") };
+    msg::reply::<IoFToken>(ftoken.into(), 0)
+        .expect("Failed to encode or reply with `IoFToken` from `state()`");
+}
+
+impl From<FToken> for IoFToken {
+    fn from(value: FToken) -> Self {
+        let FToken {
+            admin,
+            name,
+            symbol,
+            decimals,
+            total_supply,
+            balances,
+            allowances,
+        } = value;
+
+        let balances = balances
+            .iter()
+            .map(|(id, balance)| (*id, *balance))
+            .collect();
+        let allowances = allowances
+            .iter()
+            .map(|(ids, allowance)| (*ids, *allowance))
+            .collect();
+
+        Self {
+            admin,
+            name,
+            symbol,
+            decimals,
+            total_supply,
+            balances,
+            allowances,
+        }
+    }
+}
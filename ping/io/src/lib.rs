@@ -1,747 +1,444 @@
 #![no_std]
 
-use gmeta::{InOut, Metadata, In};
-use gstd::{prelude::*, ActorId, collections::BTreeMap,msg, exec };
-
-/* <- Borra
-
-use gear_lib::non_fungible_token::{
-    io::{NFTApproval, NFTTransfer, NFTTransferPayout},
-    delegated::DelegatedApproveMessage,
-    royalties::*,
-    token::{TokenId, TokenMetadata},
-};
-use primitive_types::H256;
-
-Borra   -> */ 
-
-
-pub type NftContractId = ActorId;
-pub type UserId = ActorId;
-pub type MatchId = ActorId;
-pub type TransactionId = u64;
-pub type GameId = usize;
-pub type InGame = bool;
-pub type NFTPrice = u128;
+use gmeta::{In, InOut, Metadata};
+use gstd::{collections::VecDeque, prelude::*, ActorId};
 
 pub struct DemoPingMetadata;
 
 impl Metadata for DemoPingMetadata {
-    type Init = In<InitContractData>;
-    type Handle = InOut<RutzoAction, RutzoEvent>;
+    type Init = In<PingInit>;
+    type Handle = InOut<PingAction, PingEvent>;
     type Others = ();
     type Reply = ();
     type Signal = ();
-    type State = InOut<RutzoStateQuery, RutzoStateReply>;//Out<Vec<String>>;
+    type State = InOut<PingStateQuery, PingStateReply>;
 }
 
-#[derive(Default, Encode, Decode, TypeInfo)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub enum RutzoStateQuery{
-    GetText,
-    GetNumber,
-    #[default]
-    All
-}
-
-#[derive(Encode, Decode, TypeInfo)]
-#[codec(crate = gstd::codec)]
-#[scale_info(crate = gstd::scale_info)]
-pub enum RutzoStateReply {
-    Text(String),
-    Number(u64),
-    All(Vec<String>)
+pub enum PingAction {
+    /// Replied with [`PingEvent::Pong`].
+    Ping,
+    /// Replied with [`PingEvent::PongWithPayload`] carrying the same text.
+    PingWithPayload(String),
+    /// Replied with [`PingEvent::Echo`] carrying the same bytes.
+    Echo(Vec<u8>),
+    /// Logs `payload` with the signer if `signature` is its sr25519
+    /// signature of [`signing_message`], replied with
+    /// [`PingEvent::SignedPong`].
+    SignedPing {
+        payload: Vec<u8>,
+        public_key: [u8; 32],
+        signature: [u8; 64],
+    },
+    /// Sends [`PingAction::Ping`] to `target` and records its health.
+    PingProgram {
+        target: ActorId,
+    },
+    /// Probes every target like [`PingAction::PingProgram`], one after another.
+    PingPrograms {
+        targets: Vec<ActorId>,
+    },
+    /// Subscribes the sender to [`PingEvent::Notification`] and
+    /// [`PingEvent::Heartbeat`].
+    Subscribe,
+    Unsubscribe,
+    /// Starts emitting a heartbeat every `interval` blocks, paid from `gas`
+    /// reserved out of this message. A beat still pending from an earlier
+    /// start is kept with its gas, the new `interval` applies after it.
+    ///
+    /// Only the owner can start or stop the heartbeat.
+    StartHeartbeat {
+        interval: u32,
+        gas: u64,
+    },
+    StopHeartbeat,
+    /// Scheduled by the program itself, replied with
+    /// [`PingEvent::UnexpectedBeat`] from anyone else.
+    Beat,
+    /// Allows or denies `sender` to log messages, `None` leaves it to the
+    /// rate limit. Only for the owner.
+    SetAccess {
+        sender: ActorId,
+        access: Option<Access>,
+    },
+    /// `None` lifts the limit, a zero capacity or refill period is replied
+    /// with [`PingEvent::InvalidRateLimit`]. Only for the owner.
+    SetRateLimit(Option<RateLimit>),
+    /// Sets the value a logged message has to attach, `None` makes them
+    /// free. Only for the owner.
+    SetPrice(Option<u128>),
+    /// Sends `amount` of the collected value to `to`. Only for the owner.
+    Withdraw {
+        to: ActorId,
+        amount: u128,
+    },
 }
 
-#[derive(Encode, Decode, TypeInfo)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub enum RutzoAction {
-    PlayGame {
-        //token_id: TokenId,
-        power: String
+pub enum PingEvent {
+    Pong,
+    PongWithPayload(String),
+    Echo(Vec<u8>),
+    SignedPong {
+        signer: ActorId,
+    },
+    /// The signature doesn't match the payload and the signer's nonce, the
+    /// message isn't logged.
+    InvalidSignature,
+    HealthChecked {
+        target: ActorId,
+        health: Health,
     },
-    MintCard {
-        token_id: u8
+    HealthReport(Vec<(ActorId, Health)>),
+    Subscribed,
+    Unsubscribed,
+    HeartbeatStarted {
+        next_beat: u32,
+    },
+    HeartbeatStopped,
+    /// The interval is zero, or the gas is below [`HEARTBEAT_GAS`] or can't
+    /// be reserved.
+    InvalidHeartbeat,
+    /// [`PingAction::Beat`] wasn't sent by the program itself.
+    UnexpectedBeat,
+    /// Sent to the subscribers on every beat.
+    Heartbeat {
+        seq: u64,
+    },
+    /// Sent to the subscribers on every logged message.
+    Notification(PingMessage),
+    AccessSet {
+        sender: ActorId,
+        access: Option<Access>,
+    },
+    RateLimitSet(Option<RateLimit>),
+    /// The capacity or the refill period is zero.
+    InvalidRateLimit,
+    /// The sender is denied, the message isn't logged.
+    Denied,
+    /// The sender is out of tokens, the message isn't logged.
+    RateLimited {
+        /// Number of blocks until the next token.
+        retry_after: u32,
+    },
+    PriceSet(Option<u128>),
+    /// Less than `price` was attached, the message isn't logged.
+    InsufficientValue {
+        price: u128,
+    },
+    Withdrawn {
+        to: ActorId,
+        amount: u128,
     },
-    SetNFTAddress(ActorId),
-    Register,
-    
-    AddNftForSale {
-        //token_metadata: TokenMetadata
+    /// Less than the withdrawn amount is collected.
+    InsufficientBalance {
+        balance: u128,
     },
-    
-    //BuyNFT(TokenId),
-    ApproveMinter(ActorId),
-    DelegateApprovedUser(ActorId)
+    /// The action is only for the owner.
+    NotOwner,
 }
 
-#[derive(Encode, Decode, TypeInfo)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub enum RutzoEvent {
-    //Minted(TokenId),
-    NFTContractSaved,
-    ErrorCallingNFTContract,
-    UserIsNotApproved(UserId),
-    UserIsNotTheOwner(UserId),
-    Approved(UserId),
-    UserApprovedNotExists(UserId),
-    ApprovedUserDeleted(UserId),
-    ErrorBuying(String),
-    InsufficientFunds(NFTPrice),
-    //NftWithTokenIdDoesNotExists(TokenId),
-    NFTWithIdNotExists(u8),
-    AccountAlreadyExist(UserId),
-    AccountNotExists(UserId),
-    AccountAlreadyInMatch(UserId),
-    QueryNotAllowed(String),
-    RegisterSucces,
-    LoginSucces,
-    UserInMatch(MatchId),
-    ErrorInJoiningMatch,
-    PurchaseSucces,
-    ReplySuccess,
-    NewPlayer(UserId),
-    MatchFinished,
-    MatchCreated,
-    UserIsAlreadyInAGame(u64),
-    /*
-    PendingTransfer(TokenId),
-    TransferSuccess(TokenId),
-    NFTIsNotApprovedByMainContract(TokenId),
-    */
-    MaxMintsReached(UserId)
+pub enum Health {
+    /// Replied with [`PingEvent::Pong`] after `latency` blocks.
+    Alive { latency: u32 },
+    /// Didn't reply within [`HEALTH_CHECK_TIMEOUT`] blocks.
+    Timeout,
+    /// Couldn't be reached or replied with something else.
+    Unreachable,
 }
 
- #[derive(Default, Encode, Decode, TypeInfo)]
+/// The latest health check of a target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub enum PingStateQuery {
-    GetText,
-    GetNumber,
-    #[default]
-    All
+pub struct HealthCheck {
+    /// Block at which the probe was sent.
+    pub block: u32,
+    pub health: Health,
 }
 
-#[derive(Encode, Decode, TypeInfo)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub enum PingStateReply {
-    All(Vec<String>),
-    Text(String),
-    Number(u64)
+pub enum Access {
+    /// Never rate limited.
+    Allowed,
+    /// Can't log messages at all.
+    Denied,
 }
 
-
-#[derive(Encode, Decode, TypeInfo)]
+/// A token bucket per sender, each logged message takes a token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub struct InitContractData {
-    pub nft_contract: Option<ActorId>,
-    pub tokens_metadata_default: Vec<u8>,
+pub struct RateLimit {
+    /// Number of tokens a bucket holds at most, a new one starts full.
+    pub capacity: u32,
+    /// Number of blocks it takes to add a token.
+    pub refill_blocks: u32,
 }
 
-/*
-
-Ignora de aqui en adelante, es codigo para checar --------------------------------------
-
-#[derive(Encode, Decode, TypeInfo)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub struct InitContractData {
-    pub nft_contract: Option<ActorId>,
-    pub tokens_metadata_default: Vec<TokenMetadata>,
+pub struct AccessState {
+    pub allowed: Vec<ActorId>,
+    pub denied: Vec<ActorId>,
+    pub rate_limit: Option<RateLimit>,
 }
-*/
-
-/*
 
-#[derive(Encode, Decode, TypeInfo)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub enum GameState {
-    WaitigForPlayer,
-    GameInProgress
+pub struct PaymentState {
+    pub price: Option<u128>,
+    /// Value collected over all time.
+    pub total_collected: u128,
+    /// Value collected and not withdrawn yet.
+    pub balance: u128,
+    pub contributions: Vec<(ActorId, u128)>,
 }
 
-#[derive(Encode, Decode, TypeInfo, Default, Clone)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub struct UserDataState {
-    current_game: Option<u64>,
-    past_games: Vec<u64>,
+pub struct HeartbeatState {
+    pub interval: u32,
+    /// Number of the last emitted beat.
+    pub seq: u64,
+    /// Block at which the next beat is due, `None` while stopped.
+    pub next_beat: Option<u32>,
+    /// Beats that came late or couldn't be scheduled for lack of gas.
+    pub missed: u64,
 }
 
-impl From<UserGameData> for UserDataState {
-    fn from(value: UserGameData) -> Self {
-        let current_game = if let Some(game_id) = value.current_game {
-            Some(game_id as u64)
-        } else {
-            None  
-        };
-        let past_games = value.past_games
-            .iter()
-            .map(|game_id| *game_id as u64)
-            .collect();
-        Self {
-            current_game,
-            past_games,
-        }
-    }
-}
-
-#[derive(Encode, Decode, TypeInfo, Default, Clone)]
+/// A message stored in the log.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub struct UserDefaultMints {
-    nfts_minted: Vec<u8>,
-    can_mint: bool
+pub struct PingMessage {
+    /// Sequential number of the message, used as the pagination cursor.
+    pub id: u64,
+    pub sender: ActorId,
+    pub block: u32,
+    pub timestamp: u64,
+    /// `PING` for [`PingAction::Ping`], the text or the bytes otherwise.
+    pub payload: Vec<u8>,
+    /// The verified signer of a [`PingAction::SignedPing`].
+    pub signer: Option<ActorId>,
 }
 
-#[derive(Encode, Decode, TypeInfo)]
+/// Which messages the log keeps, the oldest ones are dropped first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub struct ContractState {
-    pub owner: ActorId,
-    pub nft_contract: Option<NftContractId>,
-    pub games: Vec<MatchInformation>,
-    pub games_waiting: Vec<u64>,
-    pub games_information_by_user: Vec<(UserId, UserDataState)>,
-    pub game_id: u64,
-    pub tokens_metadata_default: Vec<(u8, TokenMetadata)>,
-    pub nfts_for_sale: Vec<(TokenId, NFTPrice)>,
-    pub default_tokens_minted_by_id: Vec<(UserId, UserDefaultMints)>,
-    pub approved_minters: Vec<UserId>,
-    pub transaction_id: TransactionId,
-    pub pending_transfers: Vec<(UserId, (UserId, TokenId))>
+pub enum Retention {
+    /// Keeps this many of the last messages.
+    Count(u32),
+    /// Keeps the messages not older than this many ms.
+    Age(u64),
 }
 
-
-
-#[derive(Encode, Decode, TypeInfo, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub struct UserData {
-    pub user_id: ActorId,
-    pub chosen_nft: TokenId,
-    pub power: u8
+pub struct MessagePage {
+    pub messages: Vec<PingMessage>,
+    /// Cursor of the next page, `None` on the last one.
+    pub next_cursor: Option<u64>,
 }
 
-#[derive(Encode, Decode, TypeInfo, Default, Clone)]
+/// Number of messages the log keeps by default.
+pub const MAX_MESSAGES: u32 = 1_000;
+/// Number of blocks a probed program has to reply.
+pub const HEALTH_CHECK_TIMEOUT: u32 = 10;
+/// Gas a beat keeps for itself, the rest is reserved for the next one.
+pub const HEARTBEAT_GAS: u64 = 5_000_000_000;
+/// Number of subscribers notified per message at most, the others are
+/// notified first on the next one.
+pub const MAX_NOTIFIED: u32 = 100;
+/// Gas given to each notification, one is only sent while the message has
+/// twice as much left.
+pub const NOTIFICATION_GAS: u64 = 1_000_000_000;
+/// Signing context of the [`PingAction::SignedPing`] signatures.
+pub const SIGNING_CONTEXT: &[u8] = b"substrate";
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub enum MatchState {
-    Finished {
-        winner: ActorId,
-        loser: ActorId
-    },
+pub enum PingStateQuery {
+    /// The payloads of the logged messages as text.
     #[default]
-    InProgress,
-    NotExists
+    All,
+    /// The whole message log, the `ping-state` metafunctions are read with it.
+    Full,
+    BySender(ActorId),
+    /// The messages with a timestamp within `from..=to`.
+    InRange {
+        from: u64,
+        to: u64,
+    },
+    /// At most `limit` messages starting from the id `cursor`.
+    Page {
+        cursor: u64,
+        limit: u32,
+    },
+    Health(ActorId),
+    AllHealth,
+    Heartbeat,
+    Subscribers,
+    Access,
+    Payments,
+    /// The messages with a verified signer.
+    Verified,
+    BySigner(ActorId),
+    /// The nonce the next [`PingAction::SignedPing`] of a signer signs.
+    Nonce(ActorId),
 }
 
-#[derive(Encode, Decode, TypeInfo)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub struct MatchInformation {
-    pub user_1: UserData,
-    pub user_2: Option<UserData>,
-    pub match_state: MatchState,
+pub enum PingStateReply {
+    All(Vec<String>),
+    Full(Vec<PingMessage>),
+    Messages(Vec<PingMessage>),
+    Page(MessagePage),
+    Health(Option<HealthCheck>),
+    AllHealth(Vec<(ActorId, HealthCheck)>),
+    Heartbeat(HeartbeatState),
+    Subscribers(Vec<ActorId>),
+    Access(AccessState),
+    Payments(PaymentState),
+    Nonce(u64),
 }
 
-#[derive(Default, Clone)]
-pub struct UserGameData {
-    current_game: Option<usize>,
-    past_games: Vec<usize>,
+impl PingMessage {
+    /// Returns the payload as text, invalid UTF-8 is replaced.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.payload).into_owned()
+    }
 }
 
-#[derive(Default)]
-pub struct Contract {
-    pub owner: ActorId,
-    pub nft_contract: Option<NftContractId>,
-    pub games: Vec<MatchInformation>,
-    pub games_waiting: Vec<GameId>,
-    pub games_information_by_user: BTreeMap<UserId, UserGameData>,
-    pub game_id: GameId,
-    pub tokens_metadata_default: BTreeMap<u8, TokenMetadata>,
-    pub nfts_for_sale: BTreeMap<TokenId, NFTPrice>,
-    pub default_tokens_minted_by_id: BTreeMap<UserId, UserDefaultMints>,
-    pub approved_minters: Vec<UserId>,
-    pub transaction_id: TransactionId,
-    pub pending_transfers: BTreeMap<UserId, (UserId, TokenId)>
+impl RateLimit {
+    /// Whether a bucket can ever hold and refill a token.
+    pub fn is_valid(&self) -> bool {
+        self.capacity > 0 && self.refill_blocks > 0
+    }
 }
 
-impl Contract {
-    pub async fn mint_new_nft(nft_contract: ActorId, token_metadata: TokenMetadata, transaction_id: u64) -> Result<NFTEvent, Error> {
-        msg::send_for_reply_as::<NFTAction, NFTEvent>(
-            nft_contract, 
-            NFTAction::Mint { 
-                transaction_id, 
-                token_metadata
-            }, 
-            0, 
-            0
-        )
-        .expect("Error in sending a message 'NFTAction::Mint' to nft contract")
-        .await
-    }
-    
-    pub async fn transfer_nft(nft_contract: ActorId, to: ActorId, token_id: TokenId, transaction_id: u64) -> Result<NFTEvent, ()>{
-        match msg::send_for_reply_as::<NFTAction, NFTEvent>(
-            nft_contract, 
-            NFTAction::Transfer { 
-                transaction_id, 
-                to, 
-                token_id
-            }, 
-            0, 
-            0
-        )
-        .expect("Error in sending a message 'NFTAction::Transfer' to nft contract")
-        .await {
-            Ok(nft_event) => {
-                let NFTEvent::Transfer(_) = nft_event else { 
-                    panic!("Unexpected answer from nft contract"); 
-                };
-                Ok(nft_event)
-            },
-            Err(_) => Err(())
-        }
-    }
-    
-    pub async fn main_contract_is_approved(nft_contract: ActorId, main_contract: ActorId, token_id: TokenId) -> Result<bool, ()> {
-        match msg::send_for_reply_as::<NFTAction, NFTEvent>(
-            nft_contract, 
-            NFTAction::IsApproved { 
-                to: main_contract,
-                token_id 
-            }, 
-            0, 
-            0
-        )
-        .expect("Error in sending a message 'NFTAction::MintFromMainContract' to a match contract")
-        .await {
-            Ok(nft_event) => {
-                let NFTEvent::IsApproved { approved, .. } = nft_event else { 
-                    panic!("Unexpected answer from nft contract"); 
-                };
-                Ok(approved)
-            },
-            Err(_) => Err(())
-        }
+impl Default for Retention {
+    fn default() -> Self {
+        Self::Count(MAX_MESSAGES)
     }
 }
 
-impl Contract {
-    pub async fn buy_nft(&mut self, user_id: UserId, token_id: TokenId, value: NFTPrice) -> (RutzoEvent, u128) {
-        if !self.is_register(&user_id) {
-            return (RutzoEvent::AccountNotExists(user_id), value);
-        }
-        
-        let token_on_sale_value = match self.nfts_for_sale.get(&token_id) {
-            Some(value) =>* value,
-            None => return (RutzoEvent::NftWithTokenIdDoesNotExists(token_id), value)
-        };
-        
-        if value < token_on_sale_value {
-            return (RutzoEvent::InsufficientFunds(value), value);
-        }
-        
-        let value_to_return = value - token_on_sale_value;
-        
-        Contract::transfer_nft(
-            self.nft_contract.unwrap(), 
-            user_id, 
-            token_id, 
-            self.transaction_id
-        )
-        .await
-        .expect("Unable to decode NFTEvent");
-        
-        (RutzoEvent::NFTContractSaved, value_to_return)
-    }
-    
-    pub async fn mint_nft_to_sale(&mut self, user_id: ActorId, token_metadata: TokenMetadata, value: NFTPrice) -> RutzoEvent {
-        let user_approved = self.approved_minters 
-            .iter()
-            .find(|&approved_user_id| *approved_user_id == user_id);
-        
-        if user_approved.is_none() && self.owner != user_id {
-            return RutzoEvent::UserIsNotApproved(user_id);
-        }
-        
-        let answer = Contract::mint_new_nft(
-            self.nft_contract.unwrap(), 
-            token_metadata, 
-            self.transaction_id
-        )
-        .await
-        .expect("Unable to decode NFTEvent");
-        
-        let NFTEvent::Transfer(NFTTransfer { token_id, .. }) = answer else {
-            panic!("Unable to decode NFTEvent");  
-        };
-        
-        self.nfts_for_sale.insert(token_id, value);
-        self.transaction_id = self.transaction_id.saturating_add(1);
-        
-        RutzoEvent::Minted(token_id)
-    }
-    
-    pub async fn mint_card(&mut self, token_id: u8) -> RutzoEvent {
-        let user_id = msg::source();
-        
-        if !self.is_register(&user_id) {
-            return RutzoEvent::AccountNotExists(user_id);
-        } 
-        
-        let nfts_minted_data = self.default_tokens_minted_by_id.get_mut(&user_id).unwrap();
-        
-        if nfts_minted_data.can_mint && nfts_minted_data.nfts_minted.len() == 3 {
-            nfts_minted_data.can_mint = false;
-            return RutzoEvent::MaxMintsReached(user_id);    
-        }
-    
-        let token_metadata = self.tokens_metadata_default
-            .get(&token_id);
-           
-        let token_metadata = match token_metadata {
-            Some(token_data) => token_data.clone(),
-            None => return RutzoEvent::NFTWithIdNotExists(token_id)
-        };
-        
-        let answer = Contract::mint_new_nft(
-            self.nft_contract.unwrap(), 
-            token_metadata, 
-            self.transaction_id
-        )
-        .await
-        .expect("Unable to decode NFTEvent");
-        
-        let NFTEvent::Transfer(transfer_data) = answer else {
-            return RutzoEvent::ErrorCallingNFTContract; 
-        };
-        
-        self.transaction_id = self.transaction_id.saturating_add(1);
-        
-        Contract::transfer_nft(
-            self.nft_contract.unwrap(),
-            user_id, 
-            transfer_data.token_id,
-            self.transaction_id
-        )
-        .await
-        .expect("Unable to decode NFTEvent");
-        
-        self.default_tokens_minted_by_id
-            .entry(user_id)
-            .and_modify(|minted_data| minted_data.nfts_minted.push(token_id))
-            .or_default();
-        
-        self.transaction_id = self.transaction_id.saturating_add(1);
-        
-        RutzoEvent::Minted(TokenId::default())
-    }
-    
-    pub fn add_minter(&mut self, user_id: ActorId) -> RutzoEvent {
-        let caller = msg::source();
-        if caller != self.owner {
-            return RutzoEvent::UserIsNotTheOwner(caller);
-        }
-        
-        self.approved_minters.push(user_id);
-        
-        RutzoEvent::Approved(user_id)
-    }
-    
-    pub fn register_user(&mut self, user_id: UserId) -> RutzoEvent {
-        if self.is_register(&user_id) {
-            return RutzoEvent::AccountAlreadyExist(user_id);
-        }
-      
-        self.games_information_by_user.insert(user_id, Default::default());       
-        self.default_tokens_minted_by_id.insert(user_id, Default::default());
-        RutzoEvent::RegisterSucces
-    }
-    
-    pub fn is_register(&self, user_id: &UserId) ->  bool {
-        self.games_information_by_user.contains_key(user_id)
-    }
-    
-    pub async fn play_game(&mut self, user_id: ActorId, token_id: TokenId, power: u8) -> RutzoEvent {
-        if !self.is_register(&user_id) {    
-            return RutzoEvent::AccountNotExists(user_id);
-        }
-        
-        let user_data = self.games_information_by_user
-            .get_mut(&user_id)
-            .unwrap();
-            
-        if let Ok(approved) = Contract::main_contract_is_approved(
-            self.nft_contract.unwrap().clone(), 
-            exec::program_id(),
-            token_id
-        ).await {
-            if !approved {
-                return RutzoEvent::NFTIsNotApprovedByMainContract(token_id);
+impl Retention {
+    /// Drops the messages of `log` that aren't retained at `now`.
+    pub fn apply(&self, log: &mut VecDeque<PingMessage>, now: u64) {
+        match *self {
+            Self::Count(count) => {
+                let excess = log.len().saturating_sub(count as usize);
+                log.drain(..excess);
             }
-        } else {
-            return RutzoEvent::NFTIsNotApprovedByMainContract(token_id);
-        }
-        
-        if let Some(&(to, token_id)) = self.pending_transfers.get(&user_id) {
-            if Contract::transfer_nft(
-                self.nft_contract.unwrap(),
-                to, 
-                token_id,
-                self.transaction_id
-            ).await.is_err() {
-                return RutzoEvent::PendingTransfer(token_id);
+            Self::Age(age) => {
+                while log
+                    .front()
+                    .is_some_and(|message| now.saturating_sub(message.timestamp) > age)
+                {
+                    log.pop_front();
+                }
             }
-            
-            self.transaction_id = self.transaction_id.saturating_add(1);
-            self.pending_transfers.remove(&user_id);
-            
-            return RutzoEvent::TransferSuccess(token_id);
-        }
-        
-        if user_data.current_game.is_some() {
-            return RutzoEvent::UserIsAlreadyInAGame(
-                user_data.current_game.unwrap() as u64
-            );
         }
-        
-        let (game_data, game_id) = match self.games_waiting.pop() {
-            None => {
-                self.games.push(MatchInformation {
-                    user_1: UserData {
-                        user_id,
-                        chosen_nft: token_id,
-                        power
-                    },
-                    user_2: None,
-                    match_state: MatchState::default()
-                });
-                self.games_waiting.push(self.game_id);
-                self.set_player_in_current_game(user_id, self.game_id);
-                self.game_id = self.game_id.saturating_add(1);
-                
-                return RutzoEvent::MatchCreated;
-            },
-            Some(game_id) => {                
-                (&mut self.games[game_id], game_id)
-            }
-        };
-        
-        game_data.user_2 = Some(UserData {
-            user_id,
-            chosen_nft: token_id,
-            power,
-        });
-        
-        let user1_power = game_data.user_1.power;
-        let user1_id = game_data.user_1.user_id;
-        
-        let (winner, loser, token_id) = if user1_power > power {
-            let winner = user1_id;
-            let loser = user_id;
-            let token_id = token_id;
-            (winner, loser, token_id)
-        } else {
-            let winner = user_id;
-            let loser = user1_id;
-            let token_id = game_data.user_1.chosen_nft;
-            (winner, loser, token_id)
-        };
-                
-        game_data.match_state = MatchState::Finished { 
-            winner, 
-            loser
-        };
-        
-        self.add_past_game_to_player(user_id, game_id);
-        self.add_past_game_to_player(user1_id, game_id);
-        self.delete_player_current_game(winner);
-        self.delete_player_current_game(loser);
-                
-        if Contract::transfer_nft(
-            self.nft_contract.unwrap(),
-            winner, 
-            token_id,
-            self.transaction_id
-        ).await.is_err() {
-            self.pending_transfers.insert(loser, (winner, token_id));
-            return RutzoEvent::PendingTransfer(token_id);
-        }
-        
-        self.transaction_id = self.transaction_id.saturating_add(1);
-        
-        RutzoEvent::MatchFinished
-    }
-    
-    pub fn set_player_in_current_game(&mut self, user_id: ActorId, game_id: usize) {
-        self.games_information_by_user
-            .entry(user_id)
-            .and_modify(|user_game_data| {
-                user_game_data.current_game = Some(game_id);
-            })
-            .or_default();
     }
-    
-    pub fn add_past_game_to_player(&mut self, user_id: ActorId, game_id: usize) {
-        self.games_information_by_user
-            .entry(user_id)
-            .and_modify(|user_game_data| {
-                user_game_data.past_games.push(game_id);
-            })
-            .or_default();
-    }
-    
-    pub fn delete_player_current_game(&mut self, user_id: ActorId) {
-        self.games_information_by_user
-            .entry(user_id)
-            .and_modify(|user_game_data| {
-                user_game_data.current_game = None;
-            })
-            .or_default();
-    }    
 }
 
-impl From<Contract> for ContractState {
-    fn from(value: Contract) -> Self {
-        let Contract {
-            owner,
-            nft_contract,
-            games_information_by_user,
-            games_waiting,
-            games,
-            game_id,
-            pending_transfers,
-            default_tokens_minted_by_id,
-            nfts_for_sale,
-            tokens_metadata_default,
-            approved_minters,
-            transaction_id,
-        } = value;
+/// Returns the messages sent by `sender`.
+pub fn messages_by_sender<'a>(
+    messages: impl IntoIterator<Item = &'a PingMessage>,
+    sender: ActorId,
+) -> Vec<PingMessage> {
+    messages
+        .into_iter()
+        .filter(|message| message.sender == sender)
+        .cloned()
+        .collect()
+}
 
-        let games_waiting = games_waiting
-            .iter()
-            .map(|match_id| *match_id as u64)
-            .collect();
-        let tokens_metadata_default = tokens_metadata_default
-            .iter()
-            .map(|(token_id, token_metadata)| (*token_id, token_metadata.clone()))
-            .collect();
-        let default_tokens_minted_by_id = default_tokens_minted_by_id
-            .iter()
-            .map(|(user_id, minted)| (*user_id, minted.clone()))
-            .collect();
-        let games_information_by_user = games_information_by_user
-            .iter()
-            .map(|(user_id, user_data)| (*user_id, user_data.clone().into()))
-            .collect();
-        let pending_transfers = pending_transfers
-            .iter()
-            .map(|(user_id, token_id)| (*user_id, *token_id))
-            .collect();
-        let nfts_for_sale = nfts_for_sale 
-            .iter()
-            .map(|(token_id, price)| (*token_id, *price) )
-            .collect();
-        let game_id = game_id as u64;
-        
-        Self {
-            owner,
-            nft_contract,
-            games,
-            games_waiting,
-            games_information_by_user,
-            game_id,
-            tokens_metadata_default,
-            nfts_for_sale,
-            default_tokens_minted_by_id,
-            approved_minters,
-            transaction_id,
-            pending_transfers
-        }
-    }
+/// Returns the messages with a verified signer.
+pub fn verified_messages<'a>(
+    messages: impl IntoIterator<Item = &'a PingMessage>,
+) -> Vec<PingMessage> {
+    messages
+        .into_iter()
+        .filter(|message| message.signer.is_some())
+        .cloned()
+        .collect()
 }
 
+/// Returns the messages verified to be signed by `signer`.
+pub fn messages_by_signer<'a>(
+    messages: impl IntoIterator<Item = &'a PingMessage>,
+    signer: ActorId,
+) -> Vec<PingMessage> {
+    messages
+        .into_iter()
+        .filter(|message| message.signer == Some(signer))
+        .cloned()
+        .collect()
+}
 
-#[derive(Debug, Encode, Decode, PartialEq, Eq, PartialOrd, Ord, Clone, TypeInfo, Hash)]
-#[codec(crate = gstd::codec)]
-#[scale_info(crate = gstd::scale_info)]
-pub enum NFTAction {
-    Mint {
-        transaction_id: u64,
-        token_metadata: TokenMetadata,
-    },
-    Burn {
-        transaction_id: u64,
-        token_id: TokenId,
-    },
-    Transfer {
-        transaction_id: u64,
-        to: ActorId,
-        token_id: TokenId,
-    },
-    TransferPayout {
-        transaction_id: u64,
-        to: ActorId,
-        token_id: TokenId,
-        amount: u128,
-    },
-    NFTPayout {
-        owner: ActorId,
-        amount: u128,
-    },
-    Approve {
-        transaction_id: u64,
-        to: ActorId,
-        token_id: TokenId,
-    },
-    DelegatedApprove {
-        transaction_id: u64,
-        message: DelegatedApproveMessage,
-        signature: [u8; 64],
-    },
-    Owner {
-        token_id: TokenId,
-    },
-    IsApproved {
-        to: ActorId,
-        token_id: TokenId,
-    },
-    Clear {
-        transaction_hash: H256,
-    },
-    AddMinter {
-        transaction_id: u64,
-        minter_id: ActorId,
-    },
+/// Returns the bytes a [`PingAction::SignedPing`] to `program` signs, the
+/// signer's nonce keeps the signature from being replayed.
+pub fn signing_message(program: ActorId, nonce: u64, payload: &[u8]) -> Vec<u8> {
+    [program.as_ref(), &nonce.to_le_bytes(), payload].concat()
+}
+
+/// Returns the messages with a timestamp within `from..=to`.
+pub fn messages_in_range<'a>(
+    messages: impl IntoIterator<Item = &'a PingMessage>,
+    from: u64,
+    to: u64,
+) -> Vec<PingMessage> {
+    messages
+        .into_iter()
+        .filter(|message| (from..=to).contains(&message.timestamp))
+        .cloned()
+        .collect()
+}
+
+/// Returns at most `limit` messages starting from the id `cursor`,
+/// `messages` must be ordered by id.
+pub fn messages_page<'a>(
+    messages: impl IntoIterator<Item = &'a PingMessage>,
+    cursor: u64,
+    limit: u32,
+) -> MessagePage {
+    let mut remaining = messages
+        .into_iter()
+        .skip_while(|message| message.id < cursor);
+    let messages: Vec<_> = remaining.by_ref().take(limit as usize).cloned().collect();
+    MessagePage {
+        messages,
+        next_cursor: remaining.next().map(|message| message.id),
+    }
 }
 
-#[derive(Debug, Encode, Decode, PartialEq, Eq, PartialOrd, Ord, Clone, TypeInfo, Hash)]
+#[derive(Encode, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub enum NFTEvent {
-    Transfer(NFTTransfer),
-    TransferPayout(NFTTransferPayout),
-    NFTPayout(Payout),
-    Approval(NFTApproval),
-    Owner {
-        owner: ActorId,
-        token_id: TokenId,
-    },
-    IsApproved {
-        to: ActorId,
-        token_id: TokenId,
-        approved: bool,
-    },
-    MinterAdded {
-        minter_id: ActorId,
-    },
+pub struct PingInit {
+    pub subscribers: Vec<ActorId>,
+    /// Falls back to [`Retention::default`] if not set.
+    pub retention: Option<Retention>,
+    /// Senders aren't rate limited if not set, the init fails if it's not
+    /// [valid](RateLimit::is_valid).
+    pub rate_limit: Option<RateLimit>,
 }
-*/
\ No newline at end of file
@@ -1,87 +1,1531 @@
 #![no_std]
 
-use gstd::{debug, msg, prelude::*, ActorId};
+use gstd::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    errors::Error,
+    exec, msg,
+    prelude::*,
+    ActorId, MessageId,
+};
 use ping_io::*;
+use schnorrkel::{PublicKey, Signature};
 
-static mut MESSAGE_LOG: Vec<String> = vec![];
+#[derive(Default)]
+struct Ping {
+    message_log: VecDeque<PingMessage>,
+    retention: Retention,
+    next_id: u64,
+    health: BTreeMap<ActorId, HealthCheck>,
+    owner: ActorId,
+    subscribers: BTreeSet<ActorId>,
+    heartbeat: HeartbeatState,
+    /// The only [`PingAction::Beat`] that is accepted and the block it's due
+    /// at. Its gas can't be taken back, so a restart reuses it.
+    pending_beat: Option<(MessageId, u32)>,
+    /// The subscriber notified last, the next notification starts after it.
+    last_notified: Option<ActorId>,
+    access: BTreeMap<ActorId, Access>,
+    rate_limit: Option<RateLimit>,
+    buckets: BTreeMap<ActorId, TokenBucket>,
+    price: Option<u128>,
+    total_collected: u128,
+    balance: u128,
+    contributions: BTreeMap<ActorId, u128>,
+    /// Number of messages logged per signer.
+    nonces: BTreeMap<ActorId, u64>,
+}
 
-#[no_mangle]
-extern "C" fn init() {
-    let init_message: InitContractData = msg::load()
-        .expect("Error in decoding InitContract");
-    
-    unsafe { MESSAGE_LOG = vec![] };
+struct TokenBucket {
+    tokens: u32,
+    /// Block at which the last token was added.
+    refilled_at: u32,
 }
 
-#[no_mangle]
-extern fn handle() {
-    let new_msg: RutzoAction = msg::load().expect("Unable to create string");
+static mut PING: Option<Ping> = None;
+
+impl TokenBucket {
+    fn new(limit: &RateLimit, now: u32) -> Self {
+        Self {
+            tokens: limit.capacity,
+            refilled_at: now,
+        }
+    }
+
+    /// Takes a token or returns the number of blocks until the next one.
+    fn take(&mut self, limit: &RateLimit, now: u32) -> Result<(), u32> {
+        let refills = now.saturating_sub(self.refilled_at) / limit.refill_blocks;
+        self.tokens = self.tokens.saturating_add(refills).min(limit.capacity);
+        self.refilled_at = if self.tokens == limit.capacity {
+            now
+        } else {
+            self.refilled_at + refills * limit.refill_blocks
+        };
+
+        if self.tokens == 0 {
+            return Err(self.refilled_at + limit.refill_blocks - now);
+        }
+        self.tokens -= 1;
+        Ok(())
+    }
+}
+
+impl Ping {
+    /// Logs the message, notifies the subscribers about it and drops the
+    /// messages that are no longer retained.
+    fn log(&mut self, payload: Vec<u8>, signer: Option<ActorId>) {
+        let timestamp = exec::block_timestamp();
+        let message = PingMessage {
+            id: self.next_id,
+            sender: msg::source(),
+            block: exec::block_height(),
+            timestamp,
+            payload,
+            signer,
+        };
+        self.message_log.push_back(message.clone());
+        self.next_id = self.next_id.saturating_add(1);
+        self.retention.apply(&mut self.message_log, timestamp);
+
+        self.notify(PingEvent::Notification(message));
+    }
+
+    /// Sends `event` to at most [`MAX_NOTIFIED`] subscribers while the gas
+    /// allows, starting after the subscriber notified last. A subscriber that
+    /// can't be sent to is skipped.
+    fn notify(&mut self, event: PingEvent) {
+        let mut subscribers: Vec<_> = self.subscribers.iter().copied().collect();
+        if let Some(last) = self.last_notified {
+            let start = subscribers.partition_point(|subscriber| *subscriber <= last);
+            subscribers.rotate_left(start);
+        }
+
+        for subscriber in subscribers.into_iter().take(MAX_NOTIFIED as usize) {
+            if exec::gas_available() < 2 * NOTIFICATION_GAS {
+                break;
+            }
+            let _ = msg::send_with_gas(subscriber, &event, NOTIFICATION_GAS, 0);
+            self.last_notified = Some(subscriber);
+        }
+    }
+
+    /// Sends `Ping` to `target` and records how it replied.
+    async fn probe(&mut self, target: ActorId) -> Health {
+        let block = exec::block_height();
+        let future = msg::send_for_reply_as::<_, PingEvent>(target, PingAction::Ping, 0, 0)
+            .and_then(|future| future.up_to(Some(HEALTH_CHECK_TIMEOUT)));
+        let reply = match future {
+            Ok(future) => future.await,
+            Err(error) => Err(error),
+        };
+
+        let health = match reply {
+            Ok(PingEvent::Pong) => Health::Alive {
+                latency: exec::block_height().saturating_sub(block),
+            },
+            Err(Error::Timeout(..)) => Health::Timeout,
+            _ => Health::Unreachable,
+        };
+        self.health.insert(target, HealthCheck { block, health });
+        health
+    }
 
-     unsafe { MESSAGE_LOG.push(String::from("ping")) };
-    /*
-    if new_msg == "PING" {
-        msg::reply_bytes("PONG", 0).expect("Unable to reply");
+    /// Sends the next beat to itself in `interval` blocks, paid from `gas`
+    /// reserved out of the current message. The reservation is given back if
+    /// the beat can't be sent.
+    fn schedule_beat(&mut self, interval: u32, gas: u64) -> Result<u32, Error> {
+        let reservation = exec::reserve_gas(gas, interval.saturating_add(1))?;
+        let beat = match msg::send_delayed_from_reservation(
+            reservation,
+            exec::program_id(),
+            PingAction::Beat,
+            0,
+            interval,
+        ) {
+            Ok(beat) => beat,
+            Err(error) => {
+                let _ = exec::unreserve_gas(reservation);
+                return Err(error);
+            }
+        };
+
+        let next_beat = exec::block_height().saturating_add(interval);
+        self.pending_beat = Some((beat, next_beat));
+        self.heartbeat.interval = interval;
+        self.heartbeat.next_beat = Some(next_beat);
+        Ok(next_beat)
+    }
+
+    /// Starts the heartbeat, a beat that is still pending is kept with the
+    /// gas reserved for it and only the interval changes.
+    fn start_heartbeat(&mut self, interval: u32, gas: u64) -> Result<u32, Error> {
+        let Some((_, due)) = self.pending_beat else {
+            return self.schedule_beat(interval, gas);
+        };
+
+        self.heartbeat.interval = interval;
+        self.heartbeat.next_beat = Some(due);
+        Ok(due)
+    }
+
+    /// Stops the heartbeat, the pending beat is ignored once it comes.
+    fn stop_heartbeat(&mut self) {
+        self.heartbeat.next_beat = None;
+    }
+
+    /// Emits a heartbeat to the subscribers and schedules the next one with
+    /// the gas left.
+    fn beat(&mut self) {
+        let HeartbeatState {
+            interval,
+            next_beat,
+            ..
+        } = self.heartbeat;
+        if let Some(due) = next_beat {
+            let late = exec::block_height().saturating_sub(due) / interval;
+            self.heartbeat.missed += u64::from(late);
+        }
+
+        self.heartbeat.seq += 1;
+        self.notify(PingEvent::Heartbeat {
+            seq: self.heartbeat.seq,
+        });
+
+        let gas = exec::gas_available().saturating_sub(HEARTBEAT_GAS);
+        if gas < HEARTBEAT_GAS || self.schedule_beat(interval, gas).is_err() {
+            self.heartbeat.missed += 1;
+            self.stop_heartbeat();
+        }
+    }
+
+    /// Takes a token from the bucket of `sender` if there's a rate limit.
+    fn take_token(&mut self, sender: ActorId) -> Result<(), PingEvent> {
+        let Some(limit) = self.rate_limit else {
+            return Ok(());
+        };
+
+        let now = exec::block_height();
+        self.buckets
+            .entry(sender)
+            .or_insert_with(|| TokenBucket::new(&limit, now))
+            .take(&limit, now)
+            .map_err(|retry_after| PingEvent::RateLimited { retry_after })
+    }
+
+    /// Verifies the sr25519 signature of `payload` and the signer's nonce,
+    /// returns the signer.
+    fn verify(
+        &self,
+        payload: &[u8],
+        public_key: &[u8; 32],
+        signature: &[u8; 64],
+    ) -> Result<ActorId, PingEvent> {
+        let signer = ActorId::new(*public_key);
+        let nonce = self.nonces.get(&signer).copied().unwrap_or_default();
+        let message = signing_message(exec::program_id(), nonce, payload);
+
+        let public_key =
+            PublicKey::from_bytes(public_key).map_err(|_| PingEvent::InvalidSignature)?;
+        let signature =
+            Signature::from_bytes(signature).map_err(|_| PingEvent::InvalidSignature)?;
+        public_key
+            .verify_simple(SIGNING_CONTEXT, &message, &signature)
+            .map_err(|_| PingEvent::InvalidSignature)?;
+        Ok(signer)
+    }
+
+    /// Checks whether `action` may be logged and collects its price out of
+    /// `value`, returns the collected amount. The sender's token is only
+    /// taken once the other checks pass, unless it's allowed.
+    fn accept(&mut self, action: &PingAction, value: u128) -> Result<u128, PingEvent> {
+        let sender = msg::source();
+        let allowed = match self.access.get(&sender) {
+            Some(Access::Allowed) => true,
+            Some(Access::Denied) => return Err(PingEvent::Denied),
+            None => false,
+        };
+        if let PingAction::SignedPing {
+            payload,
+            public_key,
+            signature,
+        } = action
+        {
+            self.verify(payload, public_key, signature)?;
+        }
+        if let Some(price) = self.price.filter(|price| value < *price) {
+            return Err(PingEvent::InsufficientValue { price });
+        }
+        if !allowed {
+            self.take_token(sender)?;
+        }
+        Ok(self.charge(sender))
     }
 
-   
+    /// Collects the price of a message from `sender`, returns the collected
+    /// amount.
+    fn charge(&mut self, sender: ActorId) -> u128 {
+        let Some(price) = self.price else {
+            return 0;
+        };
 
-        debug!("{:?} total message(s) stored: ", MESSAGE_LOG.len());
+        self.total_collected = self.total_collected.saturating_add(price);
+        self.balance = self.balance.saturating_add(price);
+        let contribution = self.contributions.entry(sender).or_default();
+        *contribution = contribution.saturating_add(price);
+        price
+    }
 
-        for log in &MESSAGE_LOG {
-            debug!("{log:?}");
+    fn check_owner(&self) -> Result<(), PingEvent> {
+        if msg::source() != self.owner {
+            return Err(PingEvent::NotOwner);
         }
+        Ok(())
     }
-    */
 }
 
-/*
 #[no_mangle]
-extern fn state() {
-    msg::reply(unsafe { MESSAGE_LOG.clone() }, 0)
-        .expect("Failed to encode or reply with `<AppMetadata as Metadata>::State` from `state()`");
+extern "C" fn init() {
+    let PingInit {
+        subscribers,
+        retention,
+        rate_limit,
+    } = msg::load().expect("Unable to decode `PingInit`");
+    assert!(
+        rate_limit.map_or(true, |limit| limit.is_valid()),
+        "Invalid rate limit"
+    );
+
+    unsafe {
+        PING = Some(Ping {
+            retention: retention.unwrap_or_default(),
+            owner: msg::source(),
+            subscribers: subscribers.into_iter().collect(),
+            rate_limit,
+            ..Default::default()
+        })
+    };
+}
+
+#[gstd::async_main]
+async fn main() {
+    let action: PingAction = msg::load().expect("Unable to decode `PingAction`");
+    let ping = unsafe { PING.as_mut().expect("The program is not initialized") };
+
+    // Everything that isn't collected goes back with the reply.
+    let mut refund = msg::value();
+    let checked = match action {
+        PingAction::Ping
+        | PingAction::PingWithPayload(_)
+        | PingAction::Echo(_)
+        | PingAction::SignedPing { .. } => ping.accept(&action, refund),
+        PingAction::StartHeartbeat { .. }
+        | PingAction::StopHeartbeat
+        | PingAction::SetAccess { .. }
+        | PingAction::SetRateLimit(_)
+        | PingAction::SetPrice(_)
+        | PingAction::Withdraw { .. } => ping.check_owner().map(|()| 0),
+        _ => Ok(0),
+    };
+    match checked {
+        Ok(collected) => refund -= collected,
+        Err(reply) => {
+            msg::reply(reply, refund).expect("Failed to encode or reply with `PingEvent`");
+            return;
+        }
+    }
+
+    let reply = match action {
+        PingAction::Ping => {
+            ping.log(b"PING".to_vec(), None);
+            PingEvent::Pong
+        }
+        PingAction::PingWithPayload(text) => {
+            ping.log(text.clone().into_bytes(), None);
+            PingEvent::PongWithPayload(text)
+        }
+        PingAction::Echo(bytes) => {
+            ping.log(bytes.clone(), None);
+            PingEvent::Echo(bytes)
+        }
+        PingAction::SignedPing {
+            payload,
+            public_key,
+            ..
+        } => {
+            let signer = ActorId::new(public_key);
+            *ping.nonces.entry(signer).or_default() += 1;
+            ping.log(payload, Some(signer));
+            PingEvent::SignedPong { signer }
+        }
+        PingAction::PingProgram { target } => PingEvent::HealthChecked {
+            target,
+            health: ping.probe(target).await,
+        },
+        PingAction::PingPrograms { targets } => {
+            let mut report = Vec::with_capacity(targets.len());
+            for target in targets {
+                report.push((target, ping.probe(target).await));
+            }
+            PingEvent::HealthReport(report)
+        }
+        PingAction::Subscribe => {
+            ping.subscribers.insert(msg::source());
+            PingEvent::Subscribed
+        }
+        PingAction::Unsubscribe => {
+            ping.subscribers.remove(&msg::source());
+            PingEvent::Unsubscribed
+        }
+        PingAction::StartHeartbeat { interval, gas } => {
+            if interval == 0 || gas < HEARTBEAT_GAS {
+                PingEvent::InvalidHeartbeat
+            } else {
+                match ping.start_heartbeat(interval, gas) {
+                    Ok(next_beat) => PingEvent::HeartbeatStarted { next_beat },
+                    Err(_) => PingEvent::InvalidHeartbeat,
+                }
+            }
+        }
+        PingAction::StopHeartbeat => {
+            ping.stop_heartbeat();
+            PingEvent::HeartbeatStopped
+        }
+        PingAction::Beat if msg::source() == exec::program_id() => {
+            if ping.pending_beat.is_some_and(|(beat, _)| beat == msg::id()) {
+                ping.pending_beat = None;
+                if ping.heartbeat.next_beat.is_some() {
+                    ping.beat();
+                }
+            }
+            return;
+        }
+        PingAction::Beat => PingEvent::UnexpectedBeat,
+        PingAction::SetAccess { sender, access } => {
+            match access {
+                Some(access) => ping.access.insert(sender, access),
+                None => ping.access.remove(&sender),
+            };
+            PingEvent::AccessSet { sender, access }
+        }
+        PingAction::SetRateLimit(rate_limit) => {
+            if rate_limit.is_some_and(|limit| !limit.is_valid()) {
+                PingEvent::InvalidRateLimit
+            } else {
+                ping.rate_limit = rate_limit;
+                ping.buckets.clear();
+                PingEvent::RateLimitSet(rate_limit)
+            }
+        }
+        PingAction::SetPrice(price) => {
+            ping.price = price;
+            PingEvent::PriceSet(price)
+        }
+        PingAction::Withdraw { to, amount } => {
+            if amount > ping.balance {
+                PingEvent::InsufficientBalance {
+                    balance: ping.balance,
+                }
+            } else {
+                msg::send_bytes(to, [], amount).expect("Failed to send the withdrawn value");
+                ping.balance -= amount;
+                PingEvent::Withdrawn { to, amount }
+            }
+        }
+    };
+
+    msg::reply(reply, refund).expect("Failed to encode or reply with `PingEvent`");
 }
-*/
 
 #[no_mangle]
 extern fn state() {
-    let state_message = msg::load()
-        .expect("Error decoding 'PingStateQuery'");
-    
-    match state_message {
-        RutzoStateQuery::GetText => {
-            msg::reply(RutzoStateReply::Text(String::from("Texto!")), 0)
-                .expect("Failed to encode or reply with <AppMetadata as Metadata>::State from 'state()'");
-        },
-        RutzoStateQuery::GetNumber => {
-            msg::reply(RutzoStateReply::Number(64), 0)
-                .expect("Failed to encode or reply with `<AppMetadata as Metadata>::State` from `state()`");
-        },
-        RutzoStateQuery::All => {
-            msg::reply(RutzoStateReply::All(unsafe { MESSAGE_LOG.clone() }), 0)
-                .expect("Failed to encode or reply with `<AppMetadata as Metadata>::State` from `state()`");
+    let Ping {
+        message_log,
+        health,
+        heartbeat,
+        subscribers,
+        access,
+        rate_limit,
+        price,
+        total_collected,
+        balance,
+        contributions,
+        nonces,
+        ..
+    } = unsafe {
+        PING.take().expect("Unexpected error in taking state")
+    };
+    let query: PingStateQuery = msg::load()
+        .expect("Unable to decode `PingStateQuery`");
+
+    let reply = match query {
+        PingStateQuery::All => {
+            PingStateReply::All(message_log.iter().map(PingMessage::text).collect())
         }
-    }
+        PingStateQuery::Full => PingStateReply::Full(message_log.into()),
+        PingStateQuery::BySender(sender) => {
+            PingStateReply::Messages(messages_by_sender(&message_log, sender))
+        }
+        PingStateQuery::InRange { from, to } => {
+            PingStateReply::Messages(messages_in_range(&message_log, from, to))
+        }
+        PingStateQuery::Page { cursor, limit } => {
+            PingStateReply::Page(messages_page(&message_log, cursor, limit))
+        }
+        PingStateQuery::Health(target) => PingStateReply::Health(health.get(&target).copied()),
+        PingStateQuery::AllHealth => PingStateReply::AllHealth(health.into_iter().collect()),
+        PingStateQuery::Heartbeat => PingStateReply::Heartbeat(heartbeat),
+        PingStateQuery::Subscribers => {
+            PingStateReply::Subscribers(subscribers.into_iter().collect())
+        }
+        PingStateQuery::Access => {
+            let (allowed, denied): (Vec<_>, Vec<_>) = access
+                .into_iter()
+                .partition(|(_, access)| *access == Access::Allowed);
+            PingStateReply::Access(AccessState {
+                allowed: allowed.into_iter().map(|(sender, _)| sender).collect(),
+                denied: denied.into_iter().map(|(sender, _)| sender).collect(),
+                rate_limit,
+            })
+        }
+        PingStateQuery::Payments => PingStateReply::Payments(PaymentState {
+            price,
+            total_collected,
+            balance,
+            contributions: contributions.into_iter().collect(),
+        }),
+        PingStateQuery::Verified => PingStateReply::Messages(verified_messages(&message_log)),
+        PingStateQuery::BySigner(signer) => {
+            PingStateReply::Messages(messages_by_signer(&message_log, signer))
+        }
+        PingStateQuery::Nonce(signer) => {
+            PingStateReply::Nonce(nonces.get(&signer).copied().unwrap_or_default())
+        }
+    };
+
+    msg::reply(reply, 0)
+        .expect("Failed to encode or reply with `PingStateReply` from `state()`");
 }
 
 #[cfg(test)]
 mod tests {
     extern crate std;
 
-    use gstd::{Encode, String};
+    use gstd::{prelude::*, ActorId};
     use gtest::{Log, Program, System};
+    use ping_io::*;
+    use schnorrkel::{ExpansionMode, Keypair, MiniSecretKey};
+
+    const META_WASM: &str = "../target/wasm32-unknown-unknown/debug/ping_state.meta.wasm";
+
+    fn read_meta<A: Encode, D: Decode>(
+        program: &Program<'_>,
+        function: &str,
+        argument: Option<A>,
+    ) -> D {
+        let wasm = std::fs::read(META_WASM).expect("Can't read metawasm");
+        program
+            .read_state_using_wasm(PingStateQuery::Full, function, wasm, argument)
+            .expect("Can't read state using metawasm")
+    }
+
+    fn read_state(program: &Program<'_>, query: PingStateQuery) -> PingStateReply {
+        program.read_state(query).expect("Can't read state")
+    }
+
+    fn message_ids(messages: &[PingMessage]) -> Vec<u64> {
+        messages.iter().map(|message| message.id).collect()
+    }
+
+    fn init_ping_with(system: &System, init: PingInit) -> Program<'_> {
+        let program = Program::current_opt(system);
+
+        let res = program.send(42, init);
+        assert!(!res.main_failed());
+
+        program
+    }
+
+    fn init_ping(system: &System) -> Program<'_> {
+        init_ping_with(
+            system,
+            PingInit {
+                subscribers: vec![],
+                retention: None,
+                rate_limit: None,
+            },
+        )
+    }
 
     #[test]
     fn it_works() {
         let system = System::new();
         system.init_logger();
 
-        let program = Program::current_opt(&system);
+        let program = init_ping(&system);
+
+        let res = program.send(42, PingAction::Ping);
+        let log = Log::builder().source(1).dest(42).payload(PingEvent::Pong);
+        assert!(res.contains(&log));
+    }
+
+    #[test]
+    fn ping_with_payload() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
+
+        let res = program.send(42, PingAction::PingWithPayload(String::from("Hello")));
+        let log = Log::builder()
+            .source(1)
+            .dest(42)
+            .payload(PingEvent::PongWithPayload(String::from("Hello")));
+        assert!(res.contains(&log));
+    }
+
+    #[test]
+    fn echo() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
+
+        let res = program.send(42, PingAction::Echo(vec![0, 1, 2]));
+        let log = Log::builder()
+            .source(1)
+            .dest(42)
+            .payload(PingEvent::Echo(vec![0, 1, 2]));
+        assert!(res.contains(&log));
+    }
+
+    #[test]
+    fn message_log() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
+
+        let res = program.send(42, PingAction::Ping);
+        assert!(!res.main_failed());
+        let res = program.send(43, PingAction::PingWithPayload(String::from("Hello")));
+        assert!(!res.main_failed());
+        let res = program.send(44, PingAction::Echo(b"bytes".to_vec()));
+        assert!(!res.main_failed());
+
+        let PingStateReply::All(messages) = program
+            .read_state(PingStateQuery::All)
+            .expect("Can't read state")
+        else {
+            panic!("Unexpected state reply");
+        };
+        assert_eq!(messages, ["PING", "Hello", "bytes"]);
+    }
+
+    #[test]
+    fn queries() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
+
+        let res = program.send(42, PingAction::PingWithPayload(String::from("Hello")));
+        assert!(!res.main_failed());
+
+        let PingStateReply::Full(messages) = read_state(&program, PingStateQuery::Full) else {
+            panic!("Unexpected state reply");
+        };
+        let [message] = &messages[..] else {
+            panic!("Unexpected messages: {messages:?}");
+        };
+        assert_eq!(message.id, 0);
+        assert_eq!(message.sender, 42.into());
+        assert_eq!(message.payload, b"Hello");
+        assert!(message.block > 0 && message.timestamp > 0);
+    }
+
+    #[test]
+    fn retention() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping_with(
+            &system,
+            PingInit {
+                subscribers: vec![],
+                retention: Some(Retention::Count(2)),
+                rate_limit: None,
+            },
+        );
+        for _ in 0..3 {
+            let res = program.send(42, PingAction::Ping);
+            assert!(!res.main_failed());
+        }
+
+        let PingStateReply::Full(messages) = read_state(&program, PingStateQuery::Full) else {
+            panic!("Unexpected state reply");
+        };
+        assert_eq!(message_ids(&messages), [1, 2]);
+
+        let program = init_ping_with(
+            &system,
+            PingInit {
+                subscribers: vec![],
+                retention: Some(Retention::Age(5_000)),
+                rate_limit: None,
+            },
+        );
+        let res = program.send(42, PingAction::Ping);
+        assert!(!res.main_failed());
+        system.spend_blocks(10);
+        let res = program.send(42, PingAction::Ping);
+        assert!(!res.main_failed());
+
+        let PingStateReply::Full(messages) = read_state(&program, PingStateQuery::Full) else {
+            panic!("Unexpected state reply");
+        };
+        assert_eq!(message_ids(&messages), [1]);
+    }
+
+    #[test]
+    fn filters() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
+        for sender in [42, 43, 42] {
+            let res = program.send(sender, PingAction::Ping);
+            assert!(!res.main_failed());
+        }
+        let PingStateReply::Full(log) = read_state(&program, PingStateQuery::Full) else {
+            panic!("Unexpected state reply");
+        };
+
+        let PingStateReply::Messages(messages) =
+            read_state(&program, PingStateQuery::BySender(42.into()))
+        else {
+            panic!("Unexpected state reply");
+        };
+        assert_eq!(message_ids(&messages), [0, 2]);
+
+        let PingStateReply::Messages(messages) = read_state(
+            &program,
+            PingStateQuery::InRange {
+                from: log[1].timestamp,
+                to: log[2].timestamp,
+            },
+        ) else {
+            panic!("Unexpected state reply");
+        };
+        assert_eq!(message_ids(&messages), [1, 2]);
+
+        let PingStateReply::Page(page) = read_state(
+            &program,
+            PingStateQuery::Page {
+                cursor: 0,
+                limit: 2,
+            },
+        ) else {
+            panic!("Unexpected state reply");
+        };
+        assert_eq!(message_ids(&page.messages), [0, 1]);
+        assert_eq!(page.next_cursor, Some(2));
+
+        let PingStateReply::Page(page) = read_state(
+            &program,
+            PingStateQuery::Page {
+                cursor: 2,
+                limit: 2,
+            },
+        ) else {
+            panic!("Unexpected state reply");
+        };
+        assert_eq!(message_ids(&page.messages), [2]);
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[test]
+    fn metafunctions() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
 
-        let res = program.send_bytes(42, "INIT");
+        let res = program.send(42, PingAction::Ping);
+        assert!(!res.main_failed());
+        let res = program.send(43, PingAction::PingWithPayload(String::from("Hello")));
         assert!(!res.main_failed());
 
-        let res = program.send_bytes(42, String::from("PING").encode());
-        let log = Log::builder().source(1).dest(42).payload_bytes("PONG");
+        let first: String = read_meta(&program, "get_first_message", None::<()>);
+        assert_eq!(first, "PING");
+        let last: String = read_meta(&program, "get_last_message", None::<()>);
+        assert_eq!(last, "Hello");
+        let len: u64 = read_meta(&program, "get_messages_len", None::<()>);
+        assert_eq!(len, 2);
+        let message: String = read_meta(&program, "get_message", Some(1u64));
+        assert_eq!(message, "Hello");
+        let sender: ActorId = read_meta(&program, "get_sender", Some(1u64));
+        assert_eq!(sender, 43.into());
+
+        let messages: Vec<PingMessage> =
+            read_meta(&program, "get_messages_by_sender", Some(ActorId::from(43)));
+        assert_eq!(message_ids(&messages), [1]);
+        let messages: Vec<PingMessage> = read_meta(
+            &program,
+            "get_messages_in_range",
+            Some((0u64, u64::MAX)),
+        );
+        assert_eq!(message_ids(&messages), [0, 1]);
+        let page: MessagePage = read_meta(&program, "get_page", Some((1u64, 10u32)));
+        assert_eq!(message_ids(&page.messages), [1]);
+        assert_eq!(page.next_cursor, None);
+    }
+
+    fn health(program: &Program<'_>, target: u64) -> Option<HealthCheck> {
+        let PingStateReply::Health(health) =
+            read_state(program, PingStateQuery::Health(target.into()))
+        else {
+            panic!("Unexpected state reply");
+        };
+
+        health
+    }
+
+    #[test]
+    fn health_check() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
+        let target = init_ping(&system);
+
+        let res = program.send(42, PingAction::PingProgram { target: 2.into() });
+        assert!(!res.main_failed());
+
+        let check = health(&program, 2).expect("Target wasn't checked");
+        assert!(matches!(check.health, Health::Alive { .. }));
+        let log = Log::builder()
+            .source(1)
+            .dest(42)
+            .payload(PingEvent::HealthChecked {
+                target: 2.into(),
+                health: check.health,
+            });
         assert!(res.contains(&log));
+
+        // Probes aren't logged by the checker, only by the target.
+        let PingStateReply::Full(messages) = read_state(&program, PingStateQuery::Full) else {
+            panic!("Unexpected state reply");
+        };
+        assert!(messages.is_empty());
+        let PingStateReply::Full(messages) = read_state(&target, PingStateQuery::Full) else {
+            panic!("Unexpected state reply");
+        };
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].sender, 1.into());
     }
-}
+
+    #[test]
+    fn health_check_timeout() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
+
+        // A user account never replies to the probe.
+        let res = program.send(42, PingAction::PingProgram { target: 100.into() });
+        assert!(!res.main_failed());
+        assert_eq!(health(&program, 100), None);
+
+        system.spend_blocks(HEALTH_CHECK_TIMEOUT + 1);
+
+        let check = health(&program, 100).expect("Target wasn't checked");
+        assert_eq!(check.health, Health::Timeout);
+    }
+
+    #[test]
+    fn health_report() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
+        init_ping(&system);
+        init_ping(&system);
+
+        let res = program.send(
+            42,
+            PingAction::PingPrograms {
+                targets: vec![2.into(), 3.into()],
+            },
+        );
+        assert!(!res.main_failed());
+
+        let PingStateReply::AllHealth(report) = read_state(&program, PingStateQuery::AllHealth)
+        else {
+            panic!("Unexpected state reply");
+        };
+        let targets: Vec<ActorId> = report.iter().map(|(target, _)| *target).collect();
+        assert_eq!(targets, [2.into(), 3.into()]);
+        assert!(report
+            .iter()
+            .all(|(_, check)| matches!(check.health, Health::Alive { .. })));
+
+        let log = Log::builder()
+            .source(1)
+            .dest(42)
+            .payload(PingEvent::HealthReport(
+                report
+                    .into_iter()
+                    .map(|(target, check)| (target, check.health))
+                    .collect(),
+            ));
+        assert!(res.contains(&log));
+    }
+
+    fn heartbeat(program: &Program<'_>) -> HeartbeatState {
+        let PingStateReply::Heartbeat(heartbeat) = read_state(program, PingStateQuery::Heartbeat)
+        else {
+            panic!("Unexpected state reply");
+        };
+
+        heartbeat
+    }
+
+    #[test]
+    fn heartbeat_beats() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
+
+        let res = program.send(43, PingAction::Subscribe);
+        assert!(res.contains(&(43, PingEvent::Subscribed.encode())));
+
+        // Only the owner can start the heartbeat.
+        let start = PingAction::StartHeartbeat {
+            interval: 5,
+            gas: 10 * HEARTBEAT_GAS,
+        };
+        let res = program.send(43, start.clone());
+        assert!(res.contains(&(43, PingEvent::NotOwner.encode())));
+
+        let res = program.send(42, start);
+        let next_beat = heartbeat(&program)
+            .next_beat
+            .expect("The heartbeat isn't running");
+        assert!(res.contains(&(42, PingEvent::HeartbeatStarted { next_beat }.encode())));
+
+        for seq in 1..=2 {
+            system.spend_blocks(5);
+            let log = Log::builder()
+                .source(1)
+                .dest(43)
+                .payload(PingEvent::Heartbeat { seq });
+            assert!(system.get_mailbox(43).contains(&log));
+        }
+        let state = heartbeat(&program);
+        assert_eq!(state.seq, 2);
+        assert_eq!(state.missed, 0);
+        assert_eq!(state.next_beat, Some(next_beat + 10));
+
+        let res = program.send(42, PingAction::StopHeartbeat);
+        assert!(res.contains(&(42, PingEvent::HeartbeatStopped.encode())));
+
+        system.spend_blocks(10);
+        let state = heartbeat(&program);
+        assert_eq!(state.seq, 2);
+        assert_eq!(state.next_beat, None);
+    }
+
+    #[test]
+    fn heartbeat_restart() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
+
+        let res = program.send(
+            42,
+            PingAction::StartHeartbeat {
+                interval: 5,
+                gas: 10 * HEARTBEAT_GAS,
+            },
+        );
+        let next_beat = heartbeat(&program)
+            .next_beat
+            .expect("The heartbeat isn't running");
+        assert!(res.contains(&(42, PingEvent::HeartbeatStarted { next_beat }.encode())));
+
+        // Restarting, even after a stop, keeps the pending beat instead of
+        // reserving gas for another one.
+        let res = program.send(42, PingAction::StopHeartbeat);
+        assert!(res.contains(&(42, PingEvent::HeartbeatStopped.encode())));
+        let res = program.send(
+            42,
+            PingAction::StartHeartbeat {
+                interval: 3,
+                gas: 10 * HEARTBEAT_GAS,
+            },
+        );
+        assert!(res.contains(&(42, PingEvent::HeartbeatStarted { next_beat }.encode())));
+
+        system.spend_blocks(3);
+        let state = heartbeat(&program);
+        assert_eq!(state.seq, 1);
+        assert_eq!(state.missed, 0);
+        assert_eq!(state.next_beat, Some(next_beat + 3));
+
+        system.spend_blocks(3);
+        assert_eq!(heartbeat(&program).seq, 2);
+    }
+
+    #[test]
+    fn heartbeat_out_of_gas() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
+
+        // Enough for a single beat that can't schedule the next one.
+        let res = program.send(
+            42,
+            PingAction::StartHeartbeat {
+                interval: 5,
+                gas: HEARTBEAT_GAS,
+            },
+        );
+        assert!(!res.main_failed());
+
+        system.spend_blocks(5);
+        let state = heartbeat(&program);
+        assert_eq!(state.seq, 1);
+        assert_eq!(state.missed, 1);
+        assert_eq!(state.next_beat, None);
+
+        system.spend_blocks(5);
+        assert_eq!(heartbeat(&program).seq, 1);
+    }
+
+    #[test]
+    fn heartbeat_errors() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
+
+        for (interval, gas) in [(0, 10 * HEARTBEAT_GAS), (5, HEARTBEAT_GAS - 1)] {
+            let res = program.send(42, PingAction::StartHeartbeat { interval, gas });
+            assert!(res.contains(&(42, PingEvent::InvalidHeartbeat.encode())));
+        }
+        assert_eq!(heartbeat(&program).next_beat, None);
+
+        let res = program.send(43, PingAction::StopHeartbeat);
+        assert!(res.contains(&(43, PingEvent::NotOwner.encode())));
+
+        // A beat from anyone else is rejected and its value returned.
+        system.mint_to(43, 1_000);
+        let res = program.send_with_value(43, PingAction::Beat, 1_000);
+        assert!(res.contains(&(43, PingEvent::UnexpectedBeat.encode())));
+        system.claim_value_from_mailbox(43);
+        assert_eq!(system.balance_of(43), 1_000);
+        assert_eq!(heartbeat(&program).seq, 0);
+    }
+
+    fn subscribers(program: &Program<'_>) -> Vec<ActorId> {
+        let PingStateReply::Subscribers(subscribers) =
+            read_state(program, PingStateQuery::Subscribers)
+        else {
+            panic!("Unexpected state reply");
+        };
+
+        subscribers
+    }
+
+    fn notification(subscriber: u64, message: PingMessage) -> Log {
+        Log::builder()
+            .source(1)
+            .dest(subscriber)
+            .payload(PingEvent::Notification(message))
+    }
+
+    #[test]
+    fn subscriptions() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping_with(
+            &system,
+            PingInit {
+                subscribers: vec![43.into()],
+                retention: None,
+                rate_limit: None,
+            },
+        );
+        assert_eq!(subscribers(&program), [43.into()]);
+
+        let res = program.send(44, PingAction::Subscribe);
+        assert!(res.contains(&(44, PingEvent::Subscribed.encode())));
+        let res = program.send(43, PingAction::Unsubscribe);
+        assert!(res.contains(&(43, PingEvent::Unsubscribed.encode())));
+        assert_eq!(subscribers(&program), [44.into()]);
+
+        let res = program.send(42, PingAction::Ping);
+        assert!(!res.main_failed());
+
+        let PingStateReply::Full(messages) = read_state(&program, PingStateQuery::Full) else {
+            panic!("Unexpected state reply");
+        };
+        let log = notification(44, messages[0].clone());
+        assert!(system.get_mailbox(44).contains(&log));
+        let log = notification(43, messages[0].clone());
+        assert!(!system.get_mailbox(43).contains(&log));
+    }
+
+    #[test]
+    fn notification_cap() {
+        let system = System::new();
+        system.init_logger();
+
+        let first = 100;
+        let last = first + u64::from(MAX_NOTIFIED);
+        let program = init_ping_with(
+            &system,
+            PingInit {
+                subscribers: (first..=last).map(Into::into).collect(),
+                retention: None,
+                rate_limit: None,
+            },
+        );
+
+        let res = program.send(42, PingAction::Ping);
+        assert!(!res.main_failed());
+        let res = program.send(42, PingAction::Ping);
+        assert!(!res.main_failed());
+
+        let PingStateReply::Full(messages) = read_state(&program, PingStateQuery::Full) else {
+            panic!("Unexpected state reply");
+        };
+        // The last subscriber didn't fit into the first notification, so it
+        // is the first one notified about the second message.
+        assert!(system
+            .get_mailbox(first)
+            .contains(&notification(first, messages[0].clone())));
+        assert!(!system
+            .get_mailbox(last)
+            .contains(&notification(last, messages[0].clone())));
+        assert!(system
+            .get_mailbox(last)
+            .contains(&notification(last, messages[1].clone())));
+        assert!(system
+            .get_mailbox(first)
+            .contains(&notification(first, messages[1].clone())));
+    }
+
+    #[test]
+    fn invalid_init_rate_limit() {
+        let system = System::new();
+        system.init_logger();
+
+        for limit in [
+            RateLimit {
+                capacity: 0,
+                refill_blocks: 5,
+            },
+            RateLimit {
+                capacity: 2,
+                refill_blocks: 0,
+            },
+        ] {
+            let program = Program::current_opt(&system);
+            let res = program.send(
+                42,
+                PingInit {
+                    subscribers: vec![],
+                    retention: None,
+                    rate_limit: Some(limit),
+                },
+            );
+            assert!(res.main_failed());
+        }
+    }
+
+    #[test]
+    fn rate_limit() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
+
+        // Senders aren't rate limited unless the init or the owner sets a limit.
+        let PingStateReply::Access(state) = read_state(&program, PingStateQuery::Access) else {
+            panic!("Unexpected state reply");
+        };
+        assert_eq!(state.rate_limit, None);
+
+        for limit in [
+            RateLimit {
+                capacity: 0,
+                refill_blocks: 5,
+            },
+            RateLimit {
+                capacity: 2,
+                refill_blocks: 0,
+            },
+        ] {
+            let res = program.send(42, PingAction::SetRateLimit(Some(limit)));
+            assert!(res.contains(&(42, PingEvent::InvalidRateLimit.encode())));
+        }
+        let res = program.send(
+            43,
+            PingAction::SetRateLimit(Some(RateLimit {
+                capacity: 2,
+                refill_blocks: 5,
+            })),
+        );
+        assert!(res.contains(&(43, PingEvent::NotOwner.encode())));
+
+        let limit = RateLimit {
+            capacity: 2,
+            refill_blocks: 5,
+        };
+        let res = program.send(42, PingAction::SetRateLimit(Some(limit)));
+        assert!(res.contains(&(42, PingEvent::RateLimitSet(Some(limit)).encode())));
+
+        for _ in 0..2 {
+            let res = program.send(43, PingAction::Ping);
+            assert!(res.contains(&(43, PingEvent::Pong.encode())));
+        }
+
+        let res = program.send(43, PingAction::Ping);
+        let PingEvent::RateLimited { retry_after } =
+            PingEvent::decode(&mut res.log()[0].payload()).expect("Unexpected reply")
+        else {
+            panic!("Not rate limited");
+        };
+        assert!((1..=limit.refill_blocks).contains(&retry_after));
+
+        // Other senders have their own buckets.
+        let res = program.send(44, PingAction::Ping);
+        assert!(res.contains(&(44, PingEvent::Pong.encode())));
+
+        system.spend_blocks(retry_after);
+        let res = program.send(43, PingAction::Ping);
+        assert!(res.contains(&(43, PingEvent::Pong.encode())));
+
+        let PingStateReply::All(messages) = read_state(&program, PingStateQuery::All) else {
+            panic!("Unexpected state reply");
+        };
+        assert_eq!(messages.len(), 4);
+    }
+
+    #[test]
+    fn access() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
+
+        let res = program.send(
+            42,
+            PingAction::SetRateLimit(Some(RateLimit {
+                capacity: 1,
+                refill_blocks: 100,
+            })),
+        );
+        assert!(!res.main_failed());
+        let deny = PingAction::SetAccess {
+            sender: 44.into(),
+            access: Some(Access::Denied),
+        };
+        let res = program.send(43, deny.clone());
+        assert!(res.contains(&(43, PingEvent::NotOwner.encode())));
+        let res = program.send(42, deny);
+        assert!(!res.main_failed());
+        let res = program.send(
+            42,
+            PingAction::SetAccess {
+                sender: 43.into(),
+                access: Some(Access::Allowed),
+            },
+        );
+        assert!(!res.main_failed());
+
+        let res = program.send(44, PingAction::Ping);
+        assert!(res.contains(&(44, PingEvent::Denied.encode())));
+        for _ in 0..3 {
+            let res = program.send(43, PingAction::Ping);
+            assert!(res.contains(&(43, PingEvent::Pong.encode())));
+        }
+
+        let PingStateReply::Access(state) = read_state(&program, PingStateQuery::Access) else {
+            panic!("Unexpected state reply");
+        };
+        assert_eq!(state.allowed, [43.into()]);
+        assert_eq!(state.denied, [44.into()]);
+
+        let res = program.send(
+            42,
+            PingAction::SetAccess {
+                sender: 44.into(),
+                access: None,
+            },
+        );
+        assert!(res.contains(&(
+            42,
+            PingEvent::AccessSet {
+                sender: 44.into(),
+                access: None,
+            }
+            .encode()
+        )));
+        let res = program.send(44, PingAction::Ping);
+        assert!(res.contains(&(44, PingEvent::Pong.encode())));
+    }
+
+    #[test]
+    fn rejected_pings_keep_tokens() {
+        const PRICE: u128 = 1_000;
+
+        let system = System::new();
+        system.init_logger();
+
+        let limit = RateLimit {
+            capacity: 1,
+            refill_blocks: 100,
+        };
+        let program = init_ping_with(
+            &system,
+            PingInit {
+                subscribers: vec![],
+                retention: None,
+                rate_limit: Some(limit),
+            },
+        );
+        let PingStateReply::Access(state) = read_state(&program, PingStateQuery::Access) else {
+            panic!("Unexpected state reply");
+        };
+        assert_eq!(state.rate_limit, Some(limit));
+
+        let res = program.send(42, PingAction::SetPrice(Some(PRICE)));
+        assert!(!res.main_failed());
+
+        // Neither an underpaid nor an unsigned ping takes the only token.
+        system.mint_to(43, 4 * PRICE);
+        let res = program.send_with_value(43, PingAction::Ping, PRICE / 2);
+        assert!(res.contains(&(43, PingEvent::InsufficientValue { price: PRICE }.encode())));
+        let res = program.send_with_value(
+            43,
+            PingAction::SignedPing {
+                payload: b"forged".to_vec(),
+                public_key: [0; 32],
+                signature: [0; 64],
+            },
+            PRICE,
+        );
+        assert!(res.contains(&(43, PingEvent::InvalidSignature.encode())));
+
+        let res = program.send_with_value(43, PingAction::Ping, PRICE);
+        assert!(res.contains(&(43, PingEvent::Pong.encode())));
+        let res = program.send_with_value(43, PingAction::Ping, PRICE);
+        assert!(matches!(
+            PingEvent::decode(&mut res.log()[0].payload()),
+            Ok(PingEvent::RateLimited { .. })
+        ));
+    }
+
+    fn payments(program: &Program<'_>) -> PaymentState {
+        let PingStateReply::Payments(payments) = read_state(program, PingStateQuery::Payments)
+        else {
+            panic!("Unexpected state reply");
+        };
+
+        payments
+    }
+
+    #[test]
+    fn paid_pings() {
+        const PRICE: u128 = 1_000;
+        const BALANCE: u128 = 100_000;
+
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
+
+        let res = program.send(42, PingAction::SetPrice(Some(PRICE)));
+        assert!(res.contains(&(42, PingEvent::PriceSet(Some(PRICE)).encode())));
+
+        system.mint_to(43, BALANCE);
+        let res = program.send_with_value(43, PingAction::Ping, PRICE / 2);
+        assert!(res.contains(&(43, PingEvent::InsufficientValue { price: PRICE }.encode())));
+        let res = program.send_with_value(43, PingAction::Ping, PRICE * 3 / 2);
+        assert!(res.contains(&(43, PingEvent::Pong.encode())));
+        let res = program.send_with_value(43, PingAction::Echo(vec![1]), PRICE);
+        assert!(res.contains(&(43, PingEvent::Echo(vec![1]).encode())));
+
+        // Only the price of the logged messages is kept.
+        system.claim_value_from_mailbox(43);
+        assert_eq!(system.balance_of(43), BALANCE - 2 * PRICE);
+
+        let payments = payments(&program);
+        assert_eq!(payments.price, Some(PRICE));
+        assert_eq!(payments.total_collected, 2 * PRICE);
+        assert_eq!(payments.balance, 2 * PRICE);
+        assert_eq!(payments.contributions, [(43.into(), 2 * PRICE)]);
+
+        let res = program.send(
+            43,
+            PingAction::Withdraw {
+                to: 44.into(),
+                amount: PRICE,
+            },
+        );
+        assert!(res.contains(&(43, PingEvent::NotOwner.encode())));
+        let res = program.send(
+            42,
+            PingAction::Withdraw {
+                to: 44.into(),
+                amount: 3 * PRICE,
+            },
+        );
+        assert!(res.contains(&(
+            42,
+            PingEvent::InsufficientBalance { balance: 2 * PRICE }.encode()
+        )));
+
+        let res = program.send(
+            42,
+            PingAction::Withdraw {
+                to: 44.into(),
+                amount: PRICE,
+            },
+        );
+        assert!(res.contains(&(
+            42,
+            PingEvent::Withdrawn {
+                to: 44.into(),
+                amount: PRICE,
+            }
+            .encode()
+        )));
+        system.claim_value_from_mailbox(44);
+        assert_eq!(system.balance_of(44), PRICE);
+
+        let payments = payments(&program);
+        assert_eq!(payments.total_collected, 2 * PRICE);
+        assert_eq!(payments.balance, PRICE);
+
+        // Free pings don't keep the value either.
+        let res = program.send(42, PingAction::SetPrice(None));
+        assert!(!res.main_failed());
+        let res = program.send_with_value(43, PingAction::Ping, PRICE);
+        assert!(res.contains(&(43, PingEvent::Pong.encode())));
+        system.claim_value_from_mailbox(43);
+        assert_eq!(system.balance_of(43), BALANCE - 2 * PRICE);
+    }
+
+    fn test_keypair(seed: u8) -> Keypair {
+        MiniSecretKey::from_bytes(&[seed; 32])
+            .expect("Invalid secret key")
+            .expand_to_keypair(ExpansionMode::Ed25519)
+    }
+
+    fn signed_ping(keypair: &Keypair, nonce: u64, payload: &[u8]) -> PingAction {
+        let message = signing_message(1.into(), nonce, payload);
+        PingAction::SignedPing {
+            payload: payload.to_vec(),
+            public_key: keypair.public.to_bytes(),
+            signature: keypair.sign_simple(SIGNING_CONTEXT, &message).to_bytes(),
+        }
+    }
+
+    #[test]
+    fn signed_pings() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
+        let keypair = test_keypair(1);
+        let signer = ActorId::new(keypair.public.to_bytes());
+
+        let res = program.send(42, PingAction::Ping);
+        assert!(!res.main_failed());
+
+        let ping = signed_ping(&keypair, 0, b"audited");
+        let res = program.send(42, ping.clone());
+        assert!(res.contains(&(42, PingEvent::SignedPong { signer }.encode())));
+
+        // The same signature can't be used twice.
+        let res = program.send(43, ping);
+        assert!(res.contains(&(43, PingEvent::InvalidSignature.encode())));
+
+        let PingStateReply::Nonce(nonce) = read_state(&program, PingStateQuery::Nonce(signer))
+        else {
+            panic!("Unexpected state reply");
+        };
+        assert_eq!(nonce, 1);
+        let res = program.send(43, signed_ping(&keypair, nonce, b"again"));
+        assert!(res.contains(&(43, PingEvent::SignedPong { signer }.encode())));
+
+        let PingStateReply::Messages(messages) = read_state(&program, PingStateQuery::Verified)
+        else {
+            panic!("Unexpected state reply");
+        };
+        assert_eq!(message_ids(&messages), [1, 2]);
+        assert_eq!(messages[0].payload, b"audited");
+        assert!(messages
+            .iter()
+            .all(|message| message.signer == Some(signer)));
+
+        let PingStateReply::Messages(messages) = read_state(
+            &program,
+            PingStateQuery::BySigner(test_keypair(2).public.to_bytes().into()),
+        ) else {
+            panic!("Unexpected state reply");
+        };
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn invalid_signatures() {
+        let system = System::new();
+        system.init_logger();
+
+        let program = init_ping(&system);
+        let keypair = test_keypair(1);
+
+        let PingAction::SignedPing {
+            public_key,
+            signature,
+            ..
+        } = signed_ping(&keypair, 0, b"audited")
+        else {
+            unreachable!();
+        };
+        let tampered = PingAction::SignedPing {
+            payload: b"tampered".to_vec(),
+            public_key,
+            signature,
+        };
+        let res = program.send(42, tampered);
+        assert!(res.contains(&(42, PingEvent::InvalidSignature.encode())));
+
+        // Signed by another key.
+        let res = program.send(
+            42,
+            PingAction::SignedPing {
+                payload: b"audited".to_vec(),
+                public_key: test_keypair(2).public.to_bytes(),
+                signature,
+            },
+        );
+        assert!(res.contains(&(42, PingEvent::InvalidSignature.encode())));
+
+        // Signed for another nonce.
+        let res = program.send(42, signed_ping(&keypair, 1, b"audited"));
+        assert!(res.contains(&(42, PingEvent::InvalidSignature.encode())));
+
+        let PingStateReply::Full(messages) = read_state(&program, PingStateQuery::Full) else {
+            panic!("Unexpected state reply");
+        };
+        assert!(messages.is_empty());
+    }
+}
\ No newline at end of file
@@ -1,27 +1,65 @@
 #![no_std]
 
-use gstd::prelude::*;
+use gstd::{prelude::*, ActorId};
+use ping_io::*;
+
+/// The metafunctions read the state with [`PingStateQuery::Full`].
+fn full(state: PingStateReply) -> Vec<PingMessage> {
+    let PingStateReply::Full(messages) = state else {
+        panic!("The state must be read with `PingStateQuery::Full`");
+    };
+    messages
+}
 
 #[gmeta::metawasm]
 pub mod metafns {
-    pub type State = Vec<String>;
+    pub type State = PingStateReply;
 
     pub fn get_first_message(state: State) -> String {
-        state.first().expect("Message log is empty!").to_string()
+        super::full(state)
+            .first()
+            .expect("Message log is empty!")
+            .text()
     }
 
     pub fn get_last_message(state: State) -> String {
-        state.last().expect("Message log is empty!").to_string()
+        super::full(state)
+            .last()
+            .expect("Message log is empty!")
+            .text()
     }
 
     pub fn get_messages_len(state: State) -> u64 {
-        state.len() as u64
+        super::full(state).len() as u64
     }
 
     pub fn get_message(state: State, index: u64) -> String {
-        state
+        super::full(state)
+            .get(index as usize)
+            .expect("Invalid index!")
+            .text()
+    }
+
+    pub fn get_sender(state: State, index: u64) -> ActorId {
+        super::full(state)
             .get(index as usize)
             .expect("Invalid index!")
-            .to_string()
+            .sender
+    }
+
+    pub fn get_messages_by_sender(state: State, sender: ActorId) -> Vec<PingMessage> {
+        messages_by_sender(&super::full(state), sender)
+    }
+
+    /// Returns the messages with a timestamp within `from..=to`.
+    pub fn get_messages_in_range(state: State, range: (u64, u64)) -> Vec<PingMessage> {
+        let (from, to) = range;
+        messages_in_range(&super::full(state), from, to)
+    }
+
+    /// Returns at most `limit` messages starting from the id `cursor`.
+    pub fn get_page(state: State, cursor_and_limit: (u64, u32)) -> MessagePage {
+        let (cursor, limit) = cursor_and_limit;
+        messages_page(&super::full(state), cursor, limit)
     }
 }
@@ -0,0 +1,323 @@
+#![no_std]
+
+use gmeta::{In, InOut, Metadata};
+use gstd::{collections::BTreeMap, prelude::*, ActorId};
+use primitive_types::{H256, U256};
+
+pub type NftContractId = ActorId;
+pub type UserId = ActorId;
+pub type MatchId = ActorId;
+pub type TransactionId = u64;
+pub type GameId = usize;
+pub type InGame = bool;
+pub type NFTPrice = u128;
+pub type TokenId = U256;
+/// Royalties of a token: the recipients and their amounts.
+pub type Payout = BTreeMap<ActorId, u128>;
+
+pub struct RutzoMetadata;
+
+impl Metadata for RutzoMetadata {
+    type Init = In<InitContractData>;
+    type Handle = InOut<RutzoAction, RutzoEvent>;
+    type Others = InOut<RutzoAction, RutzoEvent>;
+    type Reply = ();
+    type Signal = ();
+    type State = InOut<RutzoStateAction, RutzoStateEvent>;
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub description: String,
+    pub media: String,
+    pub reference: String,
+}
+
+#[derive(Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum RutzoStateAction {
+    GetId,
+    Story,
+    All,
+}
+
+#[derive(Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum RutzoStateEvent {
+    Id(UserId),
+    StoryEvent(String),
+    AllEvent(ContractState),
+}
+
+#[derive(Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum RutzoAction {
+    PlayGame { token_id: TokenId, power: String },
+    MintCard { token_id: u8 },
+    SetNFTAddress(ActorId),
+    Register,
+    AddNftForSale { token_metadata: TokenMetadata },
+    BuyNFT(TokenId),
+    ApproveMinter(ActorId),
+    DelegateApprovedUser(ActorId),
+}
+
+#[derive(Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum RutzoEvent {
+    Minted(TokenId),
+    NFTContractSaved,
+    ErrorCallingNFTContract,
+    UserIsNotApproved(UserId),
+    UserIsNotTheOwner(UserId),
+    Approved(UserId),
+    UserApprovedNotExists(UserId),
+    ApprovedUserDeleted(UserId),
+    ErrorBuying(String),
+    InsufficientFunds(NFTPrice),
+    NftWithTokenIdDoesNotExists(TokenId),
+    NFTWithIdNotExists(u8),
+    AccountAlreadyExist(UserId),
+    AccountNotExists(UserId),
+    AccountAlreadyInMatch(UserId),
+    QueryNotAllowed(String),
+    RegisterSucces,
+    LoginSucces,
+    UserInMatch(MatchId),
+    ErrorInJoiningMatch,
+    PurchaseSucces,
+    ReplySuccess,
+    NewPlayer(UserId),
+    MatchFinished,
+    MatchCreated,
+    UserIsAlreadyInAGame(u64),
+    PendingTransfer(TokenId),
+    TransferSuccess(TokenId),
+    NFTIsNotApprovedByMainContract(TokenId),
+    MaxMintsReached(UserId),
+}
+
+#[derive(Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum GameState {
+    WaitigForPlayer,
+    GameInProgress,
+}
+
+#[derive(Encode, Decode, TypeInfo, Default, Clone)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct UserDataState {
+    pub current_game: Option<u64>,
+    pub past_games: Vec<u64>,
+}
+
+#[derive(Encode, Decode, TypeInfo, Default, Clone)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct UserDefaultMints {
+    pub nfts_minted: Vec<u8>,
+    pub can_mint: bool,
+}
+
+#[derive(Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct ContractState {
+    pub owner: ActorId,
+    pub nft_contract: Option<NftContractId>,
+    pub games: Vec<MatchInformation>,
+    pub games_waiting: Vec<u64>,
+    pub games_information_by_user: Vec<(UserId, UserDataState)>,
+    pub game_id: u64,
+    pub tokens_metadata_default: Vec<(u8, TokenMetadata)>,
+    pub nfts_for_sale: Vec<(TokenId, NFTPrice)>,
+    pub default_tokens_minted_by_id: Vec<(UserId, UserDefaultMints)>,
+    pub approved_minters: Vec<UserId>,
+    pub transaction_id: TransactionId,
+    pub pending_transfers: Vec<(UserId, (UserId, TokenId))>,
+}
+
+#[derive(Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct InitContractData {
+    pub nft_contract: Option<ActorId>,
+    pub tokens_metadata_default: Vec<TokenMetadata>,
+}
+
+#[derive(Encode, Decode, TypeInfo, Default)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct UserData {
+    pub user_id: ActorId,
+    pub chosen_nft: TokenId,
+    pub power: u8,
+}
+
+#[derive(Encode, Decode, TypeInfo, Default, Clone)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum MatchState {
+    Finished {
+        winner: ActorId,
+        loser: ActorId,
+    },
+    #[default]
+    InProgress,
+    NotExists,
+}
+
+#[derive(Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct MatchInformation {
+    pub user_1: UserData,
+    pub user_2: Option<UserData>,
+    pub match_state: MatchState,
+}
+
+/// Games of a user as the program keeps them, see [`UserDataState`] for
+/// the state.
+#[derive(Default, Clone)]
+pub struct UserGameData {
+    pub current_game: Option<GameId>,
+    pub past_games: Vec<GameId>,
+}
+
+impl From<UserGameData> for UserDataState {
+    fn from(value: UserGameData) -> Self {
+        Self {
+            current_game: value.current_game.map(|game_id| game_id as u64),
+            past_games: value
+                .past_games
+                .iter()
+                .map(|game_id| *game_id as u64)
+                .collect(),
+        }
+    }
+}
+
+// The NFT contract's protocol the card game talks to.
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct DelegatedApproveMessage {
+    pub token_owner_id: ActorId,
+    pub approved_actor_id: ActorId,
+    pub nft_program_id: ActorId,
+    pub token_id: TokenId,
+    pub expiration_timestamp: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct NFTTransfer {
+    pub from: ActorId,
+    pub to: ActorId,
+    pub token_id: TokenId,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct NFTTransferPayout {
+    pub from: ActorId,
+    pub to: ActorId,
+    pub token_id: TokenId,
+    pub payouts: Payout,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct NFTApproval {
+    pub owner: ActorId,
+    pub approved_account: ActorId,
+    pub token_id: TokenId,
+}
+
+#[derive(Debug, Encode, Decode, PartialEq, Eq, PartialOrd, Ord, Clone, TypeInfo, Hash)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum NFTAction {
+    Mint {
+        transaction_id: u64,
+        token_metadata: TokenMetadata,
+    },
+    Burn {
+        transaction_id: u64,
+        token_id: TokenId,
+    },
+    Transfer {
+        transaction_id: u64,
+        to: ActorId,
+        token_id: TokenId,
+    },
+    TransferPayout {
+        transaction_id: u64,
+        to: ActorId,
+        token_id: TokenId,
+        amount: u128,
+    },
+    NFTPayout {
+        owner: ActorId,
+        amount: u128,
+    },
+    Approve {
+        transaction_id: u64,
+        to: ActorId,
+        token_id: TokenId,
+    },
+    DelegatedApprove {
+        transaction_id: u64,
+        message: DelegatedApproveMessage,
+        signature: [u8; 64],
+    },
+    Owner {
+        token_id: TokenId,
+    },
+    IsApproved {
+        to: ActorId,
+        token_id: TokenId,
+    },
+    Clear {
+        transaction_hash: H256,
+    },
+    AddMinter {
+        transaction_id: u64,
+        minter_id: ActorId,
+    },
+}
+
+#[derive(Debug, Encode, Decode, PartialEq, Eq, PartialOrd, Ord, Clone, TypeInfo, Hash)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum NFTEvent {
+    Transfer(NFTTransfer),
+    TransferPayout(NFTTransferPayout),
+    NFTPayout(Payout),
+    Approval(NFTApproval),
+    Owner {
+        owner: ActorId,
+        token_id: TokenId,
+    },
+    IsApproved {
+        to: ActorId,
+        token_id: TokenId,
+        approved: bool,
+    },
+    MinterAdded {
+        minter_id: ActorId,
+    },
+}
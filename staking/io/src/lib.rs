@@ -16,6 +16,17 @@ impl Metadata for StakingMetadata {
     type State = Out<IoStaking>;
 }
 
+pub struct ReceiptMetadata;
+
+impl Metadata for ReceiptMetadata {
+    type Init = In<InitReceipt>;
+    type Handle = InOut<ReceiptAction, Result<ReceiptEvent, Error>>;
+    type Others = ();
+    type Reply = ();
+    type Signal = ();
+    type State = Out<IoReceipt>;
+}
+
 #[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
@@ -44,6 +55,16 @@ pub enum StakingAction {
     Withdraw(u128),
     UpdateStaking(InitStaking),
     GetReward,
+    /// Enables the receipt mode with the given receipt token, or disables it
+    /// with `None`. Allowed only for the owner while nothing is staked.
+    SetReceiptToken(Option<ActorId>),
+    /// Moves `amount` of the `from` position to `to`.
+    /// Allowed only for the receipt token.
+    TransferPosition {
+        from: ActorId,
+        to: ActorId,
+        amount: u128,
+    },
 }
 
 #[derive(Debug, Encode, Decode, TypeInfo)]
@@ -54,6 +75,12 @@ pub enum StakingEvent {
     Updated,
     Reward(u128),
     Withdrawn(u128),
+    ReceiptTokenUpdated(Option<ActorId>),
+    PositionTransferred {
+        from: ActorId,
+        to: ActorId,
+        amount: u128,
+    },
 }
 
 #[derive(Debug, Clone, Default, Encode, Decode, TypeInfo)]
@@ -73,6 +100,7 @@ pub struct IoStaking {
     pub stakers: Vec<(ActorId, Staker)>,
     pub transactions: BTreeMap<ActorId, Transaction<StakingAction>>,
     pub current_tid: TransactionId,
+    pub receipt_token: Option<ActorId>,
 }
 
 #[derive(Debug, Clone, Encode, Decode, TypeInfo)]
@@ -87,6 +115,10 @@ pub enum Error {
     InsufficentBalance,
     NotOwner,
     StakerNotFound,
+    StakersExist,
+    NotReceiptToken,
+    NotStakingContract,
+    Overflow,
     ContractError(String),
 }
 
@@ -98,6 +130,53 @@ pub struct Transaction<T> {
     pub action: T,
 }
 
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct InitReceipt {
+    pub staking: ActorId,
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum ReceiptAction {
+    /// Mints receipts to `to`. Allowed only for the staking contract.
+    Mint { to: ActorId, amount: u128 },
+    /// Burns receipts of `from`. Allowed only for the staking contract.
+    Burn { from: ActorId, amount: u128 },
+    /// Transfers receipts, together with the underlying position, to `to`.
+    Transfer { to: ActorId, amount: u128 },
+}
+
+#[derive(Debug, Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum ReceiptEvent {
+    Minted {
+        to: ActorId,
+        amount: u128,
+    },
+    Burned {
+        from: ActorId,
+        amount: u128,
+    },
+    Transferred {
+        from: ActorId,
+        to: ActorId,
+        amount: u128,
+    },
+}
+
+#[derive(Debug, Clone, Default, Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct IoReceipt {
+    pub staking: ActorId,
+    pub total_supply: u128,
+    pub balances: Vec<(ActorId, u128)>,
+}
+
 impl From<GstdError> for Error {
     fn from(value: GstdError) -> Self {
         Self::ContractError(value.to_string())
@@ -0,0 +1,5 @@
+use staking_io::ReceiptMetadata;
+
+fn main() {
+    gear_wasm_builder::build_with_metadata::<ReceiptMetadata>();
+}
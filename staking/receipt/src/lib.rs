@@ -0,0 +1,180 @@
+#![no_std]
+
+use gstd::{collections::HashMap, msg, prelude::*, ActorId};
+use staking_io::*;
+
+#[derive(Debug, Clone, Default)]
+struct Receipt {
+    staking: ActorId,
+    total_supply: u128,
+    balances: HashMap<ActorId, u128>,
+}
+
+static mut RECEIPT: Option<Receipt> = None;
+
+impl Receipt {
+    /// Mints receipts for a new stake
+    /// Arguments:
+    /// `to`: the staker
+    /// `amount`: the number of staked tokens
+    fn mint(&mut self, to: ActorId, amount: u128) -> Result<ReceiptEvent, Error> {
+        if msg::source() != self.staking {
+            return Err(Error::NotStakingContract);
+        }
+
+        if amount == 0 {
+            return Err(Error::ZeroAmount);
+        }
+
+        self.credit(to, amount);
+        self.total_supply = self.total_supply.saturating_add(amount);
+
+        Ok(ReceiptEvent::Minted { to, amount })
+    }
+
+    /// Burns receipts of a withdrawn stake
+    /// Arguments:
+    /// `from`: the staker
+    /// `amount`: the number of withdrawn tokens
+    fn burn(&mut self, from: ActorId, amount: u128) -> Result<ReceiptEvent, Error> {
+        if msg::source() != self.staking {
+            return Err(Error::NotStakingContract);
+        }
+
+        if amount == 0 {
+            return Err(Error::ZeroAmount);
+        }
+
+        if !self.balances.contains_key(&from) {
+            return Err(Error::StakerNotFound);
+        }
+
+        self.debit(from, amount)?;
+        self.total_supply = self.total_supply.saturating_sub(amount);
+
+        Ok(ReceiptEvent::Burned { from, amount })
+    }
+
+    /// Transfers receipts and moves the underlying position in the staking contract
+    /// The receipts are taken from the sender before the staking contract is asked to move
+    /// the position and are given back if it refuses
+    /// Arguments:
+    /// `to`: the recipient
+    /// `amount`: the number of receipts
+    async fn transfer(&mut self, to: ActorId, amount: u128) -> Result<ReceiptEvent, Error> {
+        let from = msg::source();
+
+        if amount == 0 {
+            return Err(Error::ZeroAmount);
+        }
+
+        self.debit(from, amount)?;
+
+        if let Err(error) = self.transfer_position(from, to, amount).await {
+            self.credit(from, amount);
+            return Err(error);
+        }
+
+        self.credit(to, amount);
+
+        Ok(ReceiptEvent::Transferred { from, to, amount })
+    }
+
+    /// Asks the staking contract to move `amount` of the `from` position to `to`
+    async fn transfer_position(
+        &self,
+        from: ActorId,
+        to: ActorId,
+        amount: u128,
+    ) -> Result<(), Error> {
+        msg::send_for_reply_as::<_, Result<StakingEvent, Error>>(
+            self.staking,
+            StakingAction::TransferPosition { from, to, amount },
+            0,
+            0,
+        )?
+        .await??;
+
+        Ok(())
+    }
+
+    /// Adds `amount` receipts to the balance of `to`
+    fn credit(&mut self, to: ActorId, amount: u128) {
+        self.balances
+            .entry(to)
+            .and_modify(|balance| *balance = balance.saturating_add(amount))
+            .or_insert(amount);
+    }
+
+    /// Takes `amount` receipts from the balance of `from`
+    fn debit(&mut self, from: ActorId, amount: u128) -> Result<(), Error> {
+        let balance = self
+            .balances
+            .get_mut(&from)
+            .filter(|balance| **balance >= amount)
+            .ok_or(Error::InsufficentBalance)?;
+
+        *balance -= amount;
+
+        if *balance == 0 {
+            self.balances.remove(&from);
+        }
+
+        Ok(())
+    }
+}
+
+#[gstd::async_main]
+async fn main() {
+    let receipt = unsafe { RECEIPT.get_or_insert(Receipt::default()) };
+
+    let action: ReceiptAction = msg::load().expect("Could not load Action");
+
+    let result = match action {
+        ReceiptAction::Mint { to, amount } => receipt.mint(to, amount),
+        ReceiptAction::Burn { from, amount } => receipt.burn(from, amount),
+        ReceiptAction::Transfer { to, amount } => receipt.transfer(to, amount).await,
+    };
+
+    msg::reply(result, 0).expect("Failed to encode or reply with `Result<ReceiptEvent, Error>`");
+}
+
+#[no_mangle]
+extern fn init() {
+    let InitReceipt { staking } = msg::load().expect("Unable to decode InitReceipt");
+
+    unsafe {
+        RECEIPT = Some(Receipt {
+            staking,
+            ..Default::default()
+        })
+    };
+}
+
+#[no_mangle]
+extern fn state() {
+    let receipt = unsafe { RECEIPT.take().expect("Unexpected error in taking state") };
+    msg::reply::<IoReceipt>(receipt.into(), 0)
+        .expect("Failed to encode or reply with `IoReceipt` from `state()`");
+}
+
+impl From<Receipt> for IoReceipt {
+    fn from(value: Receipt) -> Self {
+        let Receipt {
+            staking,
+            total_supply,
+            balances,
+        } = value;
+
+        let balances = balances
+            .iter()
+            .map(|(id, balance)| (*id, *balance))
+            .collect();
+
+        Self {
+            staking,
+            total_supply,
+            balances,
+        }
+    }
+}
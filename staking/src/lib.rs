@@ -19,6 +19,9 @@ struct Staking {
     stakers: HashMap<ActorId, Staker>,
     transactions: BTreeMap<ActorId, Transaction<StakingAction>>,
     current_tid: TransactionId,
+    receipt_token: Option<ActorId>,
+    /// Stakes being withdrawn while the receipts are burnt, they can't be moved meanwhile.
+    pending: HashMap<ActorId, u128>,
 }
 
 static mut STAKING: Option<Staking> = None;
@@ -64,6 +67,39 @@ impl Staking {
         Ok(())
     }
 
+    /// Mints `amount` receipts to `to` if the receipt mode is enabled.
+    async fn mint_receipts(&self, to: &ActorId, amount: u128) -> Result<(), Error> {
+        if let Some(receipt_token) = self.receipt_token {
+            msg::send_for_reply_as::<_, Result<ReceiptEvent, Error>>(
+                receipt_token,
+                ReceiptAction::Mint { to: *to, amount },
+                0,
+                0,
+            )?
+            .await??;
+        }
+
+        Ok(())
+    }
+
+    /// Burns `amount` receipts of `from` if the receipt mode is enabled.
+    async fn burn_receipts(&self, from: &ActorId, amount: u128) -> Result<(), Error> {
+        if let Some(receipt_token) = self.receipt_token {
+            msg::send_for_reply_as::<_, Result<ReceiptEvent, Error>>(
+                receipt_token,
+                ReceiptAction::Burn {
+                    from: *from,
+                    amount,
+                },
+                0,
+                0,
+            )?
+            .await??;
+        }
+
+        Ok(())
+    }
+
     /// Calculates the reward produced so far
     fn produced(&mut self) -> u128 {
         let mut elapsed_time = exec::block_timestamp() - self.produced_time;
@@ -103,12 +139,15 @@ impl Staking {
     }
 
     /// Calculates the reward of the staker that is currently available
-    /// The return value cannot be less than zero according to the algorithm
+    /// The return value cannot be less than zero according to the algorithm,
+    /// saturation only absorbs rounding of partially transferred positions
     fn calc_reward(&mut self) -> Result<u128, Error> {
         match self.stakers.get(&msg::source()) {
-            Some(staker) => Ok(self.get_max_reward(staker.balance) + staker.reward_allowed
-                - staker.reward_debt
-                - staker.distributed),
+            Some(staker) => Ok(
+                (self.get_max_reward(staker.balance) + staker.reward_allowed)
+                    .saturating_sub(staker.reward_debt)
+                    .saturating_sub(staker.distributed),
+            ),
             None => Err(Error::StakerNotFound),
         }
     }
@@ -154,6 +193,12 @@ impl Staking {
         self.transfer_tokens(&token_address, &msg::source(), &exec::program_id(), amount)
             .await?;
 
+        if let Err(error) = self.mint_receipts(&msg::source(), amount).await {
+            self.transfer_tokens(&token_address, &exec::program_id(), &msg::source(), amount)
+                .await?;
+            return Err(error);
+        }
+
         self.update_reward();
         let amount_per_token = self.get_max_reward(amount);
 
@@ -204,18 +249,20 @@ impl Staking {
         self.update_reward();
         let amount_per_token = self.get_max_reward(amount);
 
-        match self.stakers.get(&msg::source()) {
-            Some(staker) => {
-                if staker.balance < amount {
-                    return Err(Error::InsufficentBalance);
-                }
-            }
-            None => return Err(Error::StakerNotFound),
-        };
+        if self.available(&msg::source())? < amount {
+            return Err(Error::InsufficentBalance);
+        }
 
-        let token_address = self.staking_token_address;
-        self.transfer_tokens(&token_address, &exec::program_id(), &msg::source(), amount)
-            .await?;
+        *self.pending.entry(msg::source()).or_default() += amount;
+        let unstaked = self.unstake(&msg::source(), amount).await;
+        if let Some(pending) = self.pending.get_mut(&msg::source()) {
+            *pending -= amount;
+
+            if *pending == 0 {
+                self.pending.remove(&msg::source());
+            }
+        }
+        unstaked?;
 
         let staker = self
             .stakers
@@ -228,6 +275,107 @@ impl Staking {
 
         Ok(StakingEvent::Withdrawn(amount))
     }
+
+    /// Burns the receipts of `to` and sends it the staked tokens
+    async fn unstake(&mut self, to: &ActorId, amount: u128) -> Result<(), Error> {
+        self.burn_receipts(to, amount).await?;
+
+        let token_address = self.staking_token_address;
+        self.transfer_tokens(&token_address, &exec::program_id(), to, amount)
+            .await
+    }
+
+    /// Returns the part of the staker's balance that isn't being withdrawn
+    fn available(&self, staker: &ActorId) -> Result<u128, Error> {
+        let balance = self
+            .stakers
+            .get(staker)
+            .ok_or(Error::StakerNotFound)?
+            .balance;
+
+        Ok(balance.saturating_sub(self.pending.get(staker).copied().unwrap_or_default()))
+    }
+
+    /// Enables or disables the receipt mode
+    /// Arguments:
+    /// `receipt_token`: the receipt token address, `None` disables the mode
+    fn set_receipt_token(&mut self, receipt_token: Option<ActorId>) -> Result<StakingEvent, Error> {
+        if msg::source() != self.owner {
+            return Err(Error::NotOwner);
+        }
+
+        if self.total_staked > 0 {
+            return Err(Error::StakersExist);
+        }
+
+        self.receipt_token = receipt_token;
+
+        Ok(StakingEvent::ReceiptTokenUpdated(receipt_token))
+    }
+
+    /// Moves a part of the position together with its reward accounting
+    /// The moved part of `reward_debt`, `reward_allowed` and `distributed` is proportional to `amount`
+    /// Arguments:
+    /// `from`: the current holder of the position
+    /// `to`: the new holder of the position
+    /// `amount`: the number of staked tokens to move
+    fn transfer_position(
+        &mut self,
+        from: ActorId,
+        to: ActorId,
+        amount: u128,
+    ) -> Result<StakingEvent, Error> {
+        if self.receipt_token != Some(msg::source()) {
+            return Err(Error::NotReceiptToken);
+        }
+
+        if amount == 0 {
+            return Err(Error::ZeroAmount);
+        }
+
+        self.update_reward();
+
+        if self.available(&from)? < amount {
+            return Err(Error::InsufficentBalance);
+        }
+
+        let staker = self.stakers.get_mut(&from).ok_or(Error::StakerNotFound)?;
+
+        let moved = if staker.balance == amount {
+            self.stakers.remove(&from).ok_or(Error::StakerNotFound)?
+        } else {
+            let share = |value: u128| {
+                value
+                    .checked_mul(amount)
+                    .map(|product| product / staker.balance)
+                    .ok_or(Error::Overflow)
+            };
+            let moved = Staker {
+                balance: amount,
+                reward_allowed: share(staker.reward_allowed)?,
+                reward_debt: share(staker.reward_debt)?,
+                distributed: share(staker.distributed)?,
+            };
+
+            staker.balance = staker.balance.saturating_sub(moved.balance);
+            staker.reward_allowed = staker.reward_allowed.saturating_sub(moved.reward_allowed);
+            staker.reward_debt = staker.reward_debt.saturating_sub(moved.reward_debt);
+            staker.distributed = staker.distributed.saturating_sub(moved.distributed);
+
+            moved
+        };
+
+        let recipient = self.stakers.entry(to).or_default();
+
+        recipient.balance = recipient.balance.saturating_add(moved.balance);
+        recipient.reward_allowed = recipient
+            .reward_allowed
+            .saturating_add(moved.reward_allowed);
+        recipient.reward_debt = recipient.reward_debt.saturating_add(moved.reward_debt);
+        recipient.distributed = recipient.distributed.saturating_add(moved.distributed);
+
+        Ok(StakingEvent::PositionTransferred { from, to, amount })
+    }
 }
 
 #[gstd::async_main]
@@ -282,6 +430,16 @@ async fn main() {
             staking.transactions.remove(&msg_source);
             result
         }
+        StakingAction::SetReceiptToken(receipt_token) => {
+            let result = staking.set_receipt_token(receipt_token);
+            staking.transactions.remove(&msg_source);
+            result
+        }
+        StakingAction::TransferPosition { from, to, amount } => {
+            let result = staking.transfer_position(from, to, amount);
+            staking.transactions.remove(&msg_source);
+            result
+        }
     };
     msg::reply(result, 0).expect("Failed to encode or reply with `Result<StakingEvent, Error>`");
 }
@@ -331,6 +489,8 @@ impl From<Staking> for IoStaking {
             stakers,
             transactions,
             current_tid,
+            receipt_token,
+            ..
         } = value;
 
         let stakers = stakers
@@ -352,6 +512,7 @@ impl From<Staking> for IoStaking {
             stakers,
             transactions,
             current_tid,
+            receipt_token,
         }
     }
 }
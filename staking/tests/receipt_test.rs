@@ -0,0 +1,433 @@
+use gstd::{ActorId, Encode};
+use gtest::{Log, Program, System};
+use staking_io::*;
+
+mod utils;
+use utils::PROGRAMS;
+
+const RECEIPT_WASM: &str = "../target/wasm32-unknown-unknown/debug/staking_receipt.opt.wasm";
+// Users standing in for the other contract, so a test decides when their replies arrive.
+const RECEIPT_USER: u64 = 10;
+const STAKING_USER: u64 = 11;
+
+fn init_staking(sys: &System) {
+    let staking = Program::current_opt(sys);
+
+    let res = staking.send(
+        4,
+        InitStaking {
+            staking_token_address: PROGRAMS[1].into(),
+            reward_token_address: PROGRAMS[2].into(),
+            distribution_time: 10000,
+            reward_total: 1000,
+        },
+    );
+
+    assert!(res.contains(&(4, Ok::<StakingEvent, Error>(StakingEvent::Updated).encode())));
+}
+
+fn init_receipt(sys: &System) -> Program<'_> {
+    let receipt = Program::from_file(sys, RECEIPT_WASM);
+
+    let res = receipt.send(4, InitReceipt { staking: 1.into() });
+    assert!(!res.main_failed());
+
+    let receipt_id: ActorId = receipt.id().into_bytes().into();
+    let res = sys
+        .get_program(1)
+        .send(4, StakingAction::SetReceiptToken(Some(receipt_id)));
+    assert!(res.contains(&(
+        4,
+        Ok::<StakingEvent, Error>(StakingEvent::ReceiptTokenUpdated(Some(receipt_id))).encode()
+    )));
+
+    receipt
+}
+
+fn receipt_balance(receipt: &Program<'_>, holder: u64) -> u128 {
+    let state: IoReceipt = receipt.read_state().expect("Can't read state");
+    let holder: ActorId = holder.into();
+
+    state
+        .balances
+        .iter()
+        .find(|(id, _balance)| holder.eq(id))
+        .map(|(_, balance)| *balance)
+        .unwrap_or_default()
+}
+
+fn staker(staking: &Program<'_>, address: u64) -> Option<Staker> {
+    let state: IoStaking = staking.read_state().expect("Can't read state");
+    let address: ActorId = address.into();
+
+    state
+        .stakers
+        .iter()
+        .find(|(id, _staker)| address.eq(id))
+        .map(|(_, staker)| staker.clone())
+}
+
+#[test]
+fn mint_and_burn() {
+    let sys = System::new();
+    init_staking(&sys);
+    let receipt = init_receipt(&sys);
+    sys.init_logger();
+    let staking = sys.get_program(1);
+
+    let res = staking.send(5, StakingAction::Stake(1500));
+    assert!(res.contains(&(
+        5,
+        Ok::<StakingEvent, Error>(StakingEvent::StakeAccepted(1500)).encode()
+    )));
+    assert_eq!(receipt_balance(&receipt, 5), 1500);
+
+    let res = staking.send(5, StakingAction::Withdraw(500));
+    assert!(res.contains(&(
+        5,
+        Ok::<StakingEvent, Error>(StakingEvent::Withdrawn(500)).encode()
+    )));
+    assert_eq!(receipt_balance(&receipt, 5), 1000);
+}
+
+#[test]
+fn transfer_moves_position() {
+    let sys = System::new();
+    init_staking(&sys);
+    let receipt = init_receipt(&sys);
+    sys.init_logger();
+    let staking = sys.get_program(1);
+
+    let res = staking.send(5, StakingAction::Stake(1500));
+    assert!(res.contains(&(
+        5,
+        Ok::<StakingEvent, Error>(StakingEvent::StakeAccepted(1500)).encode()
+    )));
+
+    sys.spend_blocks(2);
+
+    let res = staking.send(5, StakingAction::Withdraw(300));
+    assert!(res.contains(&(
+        5,
+        Ok::<StakingEvent, Error>(StakingEvent::Withdrawn(300)).encode()
+    )));
+    let position = staker(&staking, 5).expect("Can't find staker");
+
+    let res = receipt.send(
+        5,
+        ReceiptAction::Transfer {
+            to: 6.into(),
+            amount: 1200,
+        },
+    );
+    assert!(res.contains(&(
+        5,
+        Ok::<ReceiptEvent, Error>(ReceiptEvent::Transferred {
+            from: 5.into(),
+            to: 6.into(),
+            amount: 1200,
+        })
+        .encode()
+    )));
+
+    assert_eq!(receipt_balance(&receipt, 5), 0);
+    assert_eq!(receipt_balance(&receipt, 6), 1200);
+    assert_eq!(staker(&staking, 5), None);
+    assert_eq!(staker(&staking, 6), Some(position));
+
+    let res = staking.send(6, StakingAction::Withdraw(1200));
+    assert!(res.contains(&(
+        6,
+        Ok::<StakingEvent, Error>(StakingEvent::Withdrawn(1200)).encode()
+    )));
+    assert_eq!(receipt_balance(&receipt, 6), 0);
+}
+
+#[test]
+fn partial_transfer() {
+    let sys = System::new();
+    init_staking(&sys);
+    let receipt = init_receipt(&sys);
+    sys.init_logger();
+    let staking = sys.get_program(1);
+
+    let res = staking.send(5, StakingAction::Stake(1000));
+    assert!(res.contains(&(
+        5,
+        Ok::<StakingEvent, Error>(StakingEvent::StakeAccepted(1000)).encode()
+    )));
+
+    let res = receipt.send(
+        5,
+        ReceiptAction::Transfer {
+            to: 6.into(),
+            amount: 400,
+        },
+    );
+    assert!(res.contains(&(
+        5,
+        Ok::<ReceiptEvent, Error>(ReceiptEvent::Transferred {
+            from: 5.into(),
+            to: 6.into(),
+            amount: 400,
+        })
+        .encode()
+    )));
+
+    assert_eq!(receipt_balance(&receipt, 5), 600);
+    assert_eq!(receipt_balance(&receipt, 6), 400);
+    assert_eq!(staker(&staking, 5).map(|staker| staker.balance), Some(600));
+    assert_eq!(staker(&staking, 6).map(|staker| staker.balance), Some(400));
+}
+
+#[test]
+fn failures() {
+    let sys = System::new();
+    init_staking(&sys);
+    let receipt = init_receipt(&sys);
+    sys.init_logger();
+    let staking = sys.get_program(1);
+
+    let res = staking.send(5, StakingAction::Stake(1000));
+    assert!(res.contains(&(
+        5,
+        Ok::<StakingEvent, Error>(StakingEvent::StakeAccepted(1000)).encode()
+    )));
+
+    let res = staking.send(4, StakingAction::SetReceiptToken(None));
+    assert!(res.contains(&(4, Err::<StakingEvent, Error>(Error::StakersExist).encode())));
+
+    let res = staking.send(5, StakingAction::SetReceiptToken(None));
+    assert!(res.contains(&(5, Err::<StakingEvent, Error>(Error::NotOwner).encode())));
+
+    let res = staking.send(
+        5,
+        StakingAction::TransferPosition {
+            from: 5.into(),
+            to: 6.into(),
+            amount: 1000,
+        },
+    );
+    assert!(res.contains(&(
+        5,
+        Err::<StakingEvent, Error>(Error::NotReceiptToken).encode()
+    )));
+
+    let res = receipt.send(
+        5,
+        ReceiptAction::Mint {
+            to: 5.into(),
+            amount: 1000,
+        },
+    );
+    assert!(res.contains(&(
+        5,
+        Err::<ReceiptEvent, Error>(Error::NotStakingContract).encode()
+    )));
+
+    let res = receipt.send(
+        6,
+        ReceiptAction::Transfer {
+            to: 7.into(),
+            amount: 1000,
+        },
+    );
+    assert!(res.contains(&(
+        6,
+        Err::<ReceiptEvent, Error>(Error::InsufficentBalance).encode()
+    )));
+}
+
+#[test]
+fn transfer_during_withdraw() {
+    let sys = System::new();
+    init_staking(&sys);
+    sys.init_logger();
+    let staking = sys.get_program(1);
+    let receipts = sys.get_mailbox(RECEIPT_USER);
+
+    let res = staking.send(4, StakingAction::SetReceiptToken(Some(RECEIPT_USER.into())));
+    assert!(res.contains(&(
+        4,
+        Ok::<StakingEvent, Error>(StakingEvent::ReceiptTokenUpdated(Some(RECEIPT_USER.into())))
+            .encode()
+    )));
+
+    staking.send(5, StakingAction::Stake(1000));
+    let res = receipts.reply(
+        Log::builder()
+            .dest(RECEIPT_USER)
+            .payload(ReceiptAction::Mint {
+                to: 5.into(),
+                amount: 1000,
+            }),
+        Ok::<ReceiptEvent, Error>(ReceiptEvent::Minted {
+            to: 5.into(),
+            amount: 1000,
+        }),
+        0,
+    );
+    assert!(res.contains(&(
+        5,
+        Ok::<StakingEvent, Error>(StakingEvent::StakeAccepted(1000)).encode()
+    )));
+
+    let burn = || {
+        Log::builder()
+            .dest(RECEIPT_USER)
+            .payload(ReceiptAction::Burn {
+                from: 5.into(),
+                amount: 1000,
+            })
+    };
+    let transfer_position = || {
+        staking.send(
+            RECEIPT_USER,
+            StakingAction::TransferPosition {
+                from: 5.into(),
+                to: 6.into(),
+                amount: 1000,
+            },
+        )
+    };
+
+    // The position being withdrawn can't be moved while the receipts are burnt.
+    staking.send(5, StakingAction::Withdraw(1000));
+    let res = transfer_position();
+    assert!(res.contains(&(
+        RECEIPT_USER,
+        Err::<StakingEvent, Error>(Error::InsufficentBalance).encode()
+    )));
+
+    // A refused burn releases the position.
+    let res = receipts.reply(burn(), Err::<ReceiptEvent, Error>(Error::StakerNotFound), 0);
+    assert!(res.contains(&(
+        5,
+        Err::<StakingEvent, Error>(Error::StakerNotFound).encode()
+    )));
+    assert_eq!(staker(&staking, 5).map(|staker| staker.balance), Some(1000));
+
+    staking.send(5, StakingAction::Withdraw(1000));
+    let res = transfer_position();
+    assert!(res.contains(&(
+        RECEIPT_USER,
+        Err::<StakingEvent, Error>(Error::InsufficentBalance).encode()
+    )));
+
+    let res = receipts.reply(
+        burn(),
+        Ok::<ReceiptEvent, Error>(ReceiptEvent::Burned {
+            from: 5.into(),
+            amount: 1000,
+        }),
+        0,
+    );
+    assert!(res.contains(&(
+        5,
+        Ok::<StakingEvent, Error>(StakingEvent::Withdrawn(1000)).encode()
+    )));
+
+    let state: IoStaking = staking.read_state().expect("Can't read state");
+    assert_eq!(state.total_staked, 0);
+    assert_eq!(staker(&staking, 5).map(|staker| staker.balance), Some(0));
+    assert_eq!(staker(&staking, 6), None);
+}
+
+#[test]
+fn burn_during_transfer() {
+    let sys = System::new();
+    sys.init_logger();
+    let receipt = Program::from_file(&sys, RECEIPT_WASM);
+    let staking = sys.get_mailbox(STAKING_USER);
+
+    let res = receipt.send(
+        4,
+        InitReceipt {
+            staking: STAKING_USER.into(),
+        },
+    );
+    assert!(!res.main_failed());
+
+    let res = receipt.send(
+        STAKING_USER,
+        ReceiptAction::Mint {
+            to: 5.into(),
+            amount: 1000,
+        },
+    );
+    assert!(!res.main_failed());
+
+    let transfer_position = || {
+        Log::builder()
+            .dest(STAKING_USER)
+            .payload(StakingAction::TransferPosition {
+                from: 5.into(),
+                to: 6.into(),
+                amount: 1000,
+            })
+    };
+    let burn = || {
+        receipt.send(
+            STAKING_USER,
+            ReceiptAction::Burn {
+                from: 5.into(),
+                amount: 1000,
+            },
+        )
+    };
+
+    // The receipts being transferred can't be burnt while the position is moved.
+    receipt.send(
+        5,
+        ReceiptAction::Transfer {
+            to: 6.into(),
+            amount: 1000,
+        },
+    );
+    let res = burn();
+    assert!(res.contains(&(
+        STAKING_USER,
+        Err::<ReceiptEvent, Error>(Error::StakerNotFound).encode()
+    )));
+
+    // The receipts are given back if the staking contract refuses the move.
+    let res = staking.reply(
+        transfer_position(),
+        Err::<StakingEvent, Error>(Error::InsufficentBalance),
+        0,
+    );
+    assert!(res.contains(&(
+        5,
+        Err::<ReceiptEvent, Error>(Error::InsufficentBalance).encode()
+    )));
+    assert_eq!(receipt_balance(&receipt, 5), 1000);
+    assert_eq!(receipt_balance(&receipt, 6), 0);
+
+    receipt.send(
+        5,
+        ReceiptAction::Transfer {
+            to: 6.into(),
+            amount: 1000,
+        },
+    );
+    let res = staking.reply(
+        transfer_position(),
+        Ok::<StakingEvent, Error>(StakingEvent::PositionTransferred {
+            from: 5.into(),
+            to: 6.into(),
+            amount: 1000,
+        }),
+        0,
+    );
+    assert!(res.contains(&(
+        5,
+        Ok::<ReceiptEvent, Error>(ReceiptEvent::Transferred {
+            from: 5.into(),
+            to: 6.into(),
+            amount: 1000,
+        })
+        .encode()
+    )));
+    assert_eq!(receipt_balance(&receipt, 5), 0);
+    assert_eq!(receipt_balance(&receipt, 6), 1000);
+}
@@ -0,0 +1,5 @@
+use tamagotchi_battle_io::BattleMetadata;
+
+fn main() {
+    gear_wasm_builder::build_with_metadata::<BattleMetadata>();
+}
@@ -0,0 +1,90 @@
+#![no_std]
+
+use gmeta::{In, InOut, Metadata, Out};
+use gstd::{prelude::*, ActorId};
+
+pub type TamagotchiId = ActorId;
+pub type BattleId = u64;
+
+/// Power added by every purchased attribute.
+pub const ATTRIBUTE_POWER: u64 = 1_000;
+/// A battle is won by the first tamagotchi to win this many rounds.
+pub const ROUNDS_TO_WIN: u8 = 3;
+
+pub struct BattleMetadata;
+
+impl Metadata for BattleMetadata {
+    type Init = In<InitBattle>;
+    type Handle = InOut<BattleAction, Result<BattleEvent, BattleError>>;
+    type Others = ();
+    type Reply = ();
+    type Signal = ();
+    type State = Out<IoBattle>;
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct InitBattle {
+    /// Seed of the generator that resolves the rounds.
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum BattleAction {
+    /// Registers the tamagotchi of the sender, pairing it
+    /// with the waiting one if there is any.
+    Register(TamagotchiId),
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum BattleEvent {
+    Registered(TamagotchiId),
+    BattleFinished {
+        battle_id: BattleId,
+        winner: TamagotchiId,
+    },
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum BattleError {
+    NotOwner,
+    AlreadyRegistered,
+    /// The tamagotchi failed or gave an unexpected reply.
+    TamagotchiUnavailable,
+}
+
+#[derive(Debug, Clone, Default, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct Player {
+    pub owner: ActorId,
+    pub tamagotchi_id: TamagotchiId,
+    pub power: u64,
+}
+
+#[derive(Debug, Clone, Default, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct Battle {
+    pub players: (Player, Player),
+    /// Winner of every round in order.
+    pub rounds: Vec<TamagotchiId>,
+    pub winner: TamagotchiId,
+}
+
+#[derive(Debug, Clone, Default, Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct IoBattle {
+    pub seed: u64,
+    pub waiting: Option<Player>,
+    pub battles: Vec<(BattleId, Battle)>,
+    pub wins: Vec<(TamagotchiId, u32)>,
+}
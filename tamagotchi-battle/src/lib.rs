@@ -0,0 +1,199 @@
+#![no_std]
+
+use gstd::{collections::HashMap, msg, prelude::*, ActorId};
+use rand_xoshiro::{
+    rand_core::{RngCore, SeedableRng},
+    Xoshiro256PlusPlus,
+};
+use tamagotchi_battle_io::*;
+use tamagotchi_io::{TmgAction, TmgError, TmgReply};
+
+#[derive(Debug, Clone, Default)]
+struct Arena {
+    seed: u64,
+    waiting: Option<Player>,
+    battles: HashMap<BattleId, Battle>,
+    wins: HashMap<TamagotchiId, u32>,
+}
+
+static mut ARENA: Option<Arena> = None;
+
+impl Arena {
+    /// Registers the tamagotchi of the sender and fights the waiting one
+    /// Arguments:
+    /// `tamagotchi_id`: the tamagotchi program
+    async fn register(&mut self, tamagotchi_id: TamagotchiId) -> Result<BattleEvent, BattleError> {
+        let owner = msg::source();
+
+        self.check_not_waiting(&tamagotchi_id)?;
+
+        let TmgReply::TmgInfo {
+            owner: tamagotchi_owner,
+            ..
+        } = request(tamagotchi_id, TmgAction::TmgInfo).await?
+        else {
+            return Err(BattleError::TamagotchiUnavailable);
+        };
+
+        if tamagotchi_owner != owner {
+            return Err(BattleError::NotOwner);
+        }
+
+        let TmgReply::Stats {
+            fed,
+            entertained,
+            rested,
+            attributes,
+        } = request(tamagotchi_id, TmgAction::Stats).await?
+        else {
+            return Err(BattleError::TamagotchiUnavailable);
+        };
+
+        let power = fed
+            .saturating_add(entertained)
+            .saturating_add(rested)
+            .saturating_add(ATTRIBUTE_POWER.saturating_mul(attributes.len() as u64));
+
+        // The state could change while waiting for the replies.
+        self.check_not_waiting(&tamagotchi_id)?;
+
+        let player = Player {
+            owner,
+            tamagotchi_id,
+            power,
+        };
+
+        let Some(opponent) = self.waiting.take() else {
+            self.waiting = Some(player);
+            return Ok(BattleEvent::Registered(tamagotchi_id));
+        };
+
+        let opponent_owner = opponent.owner;
+        let event = self.fight(opponent, player);
+        msg::send(opponent_owner, Ok::<_, BattleError>(event.clone()), 0)
+            .expect("Error in sending a notification to the opponent");
+
+        Ok(event)
+    }
+
+    /// Resolves the battle round by round, the chance to win a round
+    /// is proportional to the power of the tamagotchi
+    fn fight(&mut self, first: Player, second: Player) -> BattleEvent {
+        let battle_id = self.battles.len() as BattleId;
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(self.seed.wrapping_add(battle_id));
+
+        // Every tamagotchi keeps a chance even with no power left.
+        let first_weight = first.power.saturating_add(1);
+        let total_weight = first_weight.saturating_add(second.power.saturating_add(1));
+
+        let mut rounds = Vec::new();
+        let mut first_wins = 0;
+        let mut second_wins = 0;
+
+        while first_wins < ROUNDS_TO_WIN && second_wins < ROUNDS_TO_WIN {
+            if rng.next_u64() % total_weight < first_weight {
+                first_wins += 1;
+                rounds.push(first.tamagotchi_id);
+            } else {
+                second_wins += 1;
+                rounds.push(second.tamagotchi_id);
+            }
+        }
+
+        let winner = if first_wins == ROUNDS_TO_WIN {
+            first.tamagotchi_id
+        } else {
+            second.tamagotchi_id
+        };
+
+        self.wins
+            .entry(winner)
+            .and_modify(|wins| *wins = wins.saturating_add(1))
+            .or_insert(1);
+        self.battles.insert(
+            battle_id,
+            Battle {
+                players: (first, second),
+                rounds,
+                winner,
+            },
+        );
+
+        BattleEvent::BattleFinished { battle_id, winner }
+    }
+
+    fn check_not_waiting(&self, tamagotchi_id: &TamagotchiId) -> Result<(), BattleError> {
+        if self
+            .waiting
+            .as_ref()
+            .is_some_and(|player| player.tamagotchi_id == *tamagotchi_id)
+        {
+            return Err(BattleError::AlreadyRegistered);
+        }
+
+        Ok(())
+    }
+}
+
+async fn request(tamagotchi_id: ActorId, action: TmgAction) -> Result<TmgReply, BattleError> {
+    msg::send_for_reply_as::<_, Result<TmgReply, TmgError>>(tamagotchi_id, action, 0, 0)
+        .map_err(|_| BattleError::TamagotchiUnavailable)?
+        .await
+        .map_err(|_| BattleError::TamagotchiUnavailable)?
+        .map_err(|_| BattleError::TamagotchiUnavailable)
+}
+
+#[gstd::async_main]
+async fn main() {
+    let arena = unsafe { ARENA.get_or_insert(Arena::default()) };
+
+    let action: BattleAction = msg::load().expect("Could not load Action");
+
+    let result = match action {
+        BattleAction::Register(tamagotchi_id) => arena.register(tamagotchi_id).await,
+    };
+
+    msg::reply(result, 0)
+        .expect("Failed to encode or reply with `Result<BattleEvent, BattleError>`");
+}
+
+#[no_mangle]
+extern fn init() {
+    let InitBattle { seed } = msg::load().expect("Unable to decode InitBattle");
+
+    unsafe {
+        ARENA = Some(Arena {
+            seed,
+            ..Default::default()
+        })
+    };
+}
+
+#[no_mangle]
+extern fn state() {
+    let arena = unsafe { ARENA.take().expect("Unexpected error in taking state") };
+    msg::reply::<IoBattle>(arena.into(), 0)
+        .expect("Failed to encode or reply with `IoBattle` from `state()`");
+}
+
+impl From<Arena> for IoBattle {
+    fn from(value: Arena) -> Self {
+        let Arena {
+            seed,
+            waiting,
+            battles,
+            wins,
+        } = value;
+
+        let mut battles: Vec<_> = battles.into_iter().collect();
+        battles.sort_unstable_by_key(|(battle_id, _)| *battle_id);
+        let wins = wins.into_iter().collect();
+
+        Self {
+            seed,
+            waiting,
+            battles,
+            wins,
+        }
+    }
+}
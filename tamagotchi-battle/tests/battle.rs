@@ -0,0 +1,87 @@
+use gstd::Encode;
+use gtest::{Program, System};
+use tamagotchi_battle_io::*;
+
+#[path = "../../tamagotchi/tests/utils/mod.rs"]
+mod utils;
+use utils::{init_tamagotchi, program_id};
+
+const FIRST_OWNER: u64 = 3;
+const SECOND_OWNER: u64 = 4;
+
+fn init_battle(sys: &System) -> Program<'_> {
+    let battle = Program::current_opt(sys);
+
+    let res = battle.send(FIRST_OWNER, InitBattle { seed: 42 });
+    assert!(!res.main_failed());
+
+    battle
+}
+
+#[test]
+fn battle() {
+    let sys = System::new();
+    sys.init_logger();
+    let battle = init_battle(&sys);
+    let first = init_tamagotchi(&sys, FIRST_OWNER);
+    let second = init_tamagotchi(&sys, SECOND_OWNER);
+
+    let res = battle.send(FIRST_OWNER, BattleAction::Register(program_id(&first)));
+    assert!(res.contains(&(
+        FIRST_OWNER,
+        Ok::<BattleEvent, BattleError>(BattleEvent::Registered(program_id(&first))).encode()
+    )));
+
+    let res = battle.send(SECOND_OWNER, BattleAction::Register(program_id(&second)));
+    assert!(!res.main_failed());
+
+    let state: IoBattle = battle.read_state(0).expect("Can't read state");
+    assert_eq!(state.waiting, None);
+
+    let (battle_id, result) = &state.battles[0];
+    assert_eq!(*battle_id, 0);
+    assert!([program_id(&first), program_id(&second)].contains(&result.winner));
+    assert_eq!(
+        result
+            .rounds
+            .iter()
+            .filter(|winner| **winner == result.winner)
+            .count(),
+        ROUNDS_TO_WIN as usize
+    );
+    assert_eq!(state.wins, vec![(result.winner, 1)]);
+
+    let event = Ok::<BattleEvent, BattleError>(BattleEvent::BattleFinished {
+        battle_id: 0,
+        winner: result.winner,
+    })
+    .encode();
+    assert!(res.contains(&(SECOND_OWNER, event.clone())));
+    assert!(res.contains(&(FIRST_OWNER, event)));
+}
+
+#[test]
+fn failures() {
+    let sys = System::new();
+    sys.init_logger();
+    let battle = init_battle(&sys);
+    let tmg = init_tamagotchi(&sys, FIRST_OWNER);
+
+    let res = battle.send(SECOND_OWNER, BattleAction::Register(program_id(&tmg)));
+    assert!(res.contains(&(
+        SECOND_OWNER,
+        Err::<BattleEvent, BattleError>(BattleError::NotOwner).encode()
+    )));
+
+    let res = battle.send(FIRST_OWNER, BattleAction::Register(program_id(&tmg)));
+    assert!(res.contains(&(
+        FIRST_OWNER,
+        Ok::<BattleEvent, BattleError>(BattleEvent::Registered(program_id(&tmg))).encode()
+    )));
+
+    let res = battle.send(FIRST_OWNER, BattleAction::Register(program_id(&tmg)));
+    assert!(res.contains(&(
+        FIRST_OWNER,
+        Err::<BattleEvent, BattleError>(BattleError::AlreadyRegistered).encode()
+    )));
+}
@@ -0,0 +1,5 @@
+use tamagotchi_factory_io::FactoryMetadata;
+
+fn main() {
+    gear_wasm_builder::build_with_metadata::<FactoryMetadata>();
+}
@@ -0,0 +1,92 @@
+#![no_std]
+
+use gmeta::{In, InOut, Metadata};
+use gstd::{prelude::*, ActorId, CodeId};
+use tamagotchi_io::Offspring;
+
+pub type TamagotchiId = ActorId;
+
+pub struct FactoryMetadata;
+
+impl Metadata for FactoryMetadata {
+    type Init = In<InitFactory>;
+    type Handle = InOut<FactoryAction, Result<FactoryEvent, FactoryError>>;
+    type Others = ();
+    type Reply = ();
+    type Signal = ();
+    type State = InOut<FactoryStateQuery, FactoryStateReply>;
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct InitFactory {
+    pub tamagotchi_code_id: CodeId,
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum FactoryAction {
+    /// Creates a tamagotchi owned by the sender.
+    CreateTamagotchi { name: String },
+    /// Creates the offspring of two tamagotchis made by the factory for `owner`,
+    /// must be sent by the first parent.
+    CreateOffspring {
+        name: String,
+        owner: ActorId,
+        offspring: Offspring,
+    },
+    /// Sent by a tamagotchi made by the factory when it's transferred to the new owner,
+    /// keeps [`FactoryStateQuery::Tamagotchis`] up to date.
+    Transferred(ActorId),
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum FactoryEvent {
+    TamagotchiCreated {
+        tamagotchi_id: TamagotchiId,
+        owner: ActorId,
+    },
+    OffspringCreated {
+        tamagotchi_id: TamagotchiId,
+        owner: ActorId,
+        parents: (TamagotchiId, TamagotchiId),
+    },
+    Transferred {
+        tamagotchi_id: TamagotchiId,
+        owner: ActorId,
+    },
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum FactoryError {
+    /// The tamagotchi program couldn't be created or initialized.
+    CreationFailed,
+    /// The sender isn't the first parent or a parent wasn't made by the factory.
+    UnknownParents,
+    /// The sender of [`FactoryAction::Transferred`] wasn't made by the factory.
+    UnknownTamagotchi,
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum FactoryStateQuery {
+    TamagotchiCodeId,
+    Tamagotchis(ActorId),
+    All,
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum FactoryStateReply {
+    TamagotchiCodeId(CodeId),
+    Tamagotchis(Vec<TamagotchiId>),
+    All(Vec<(ActorId, Vec<TamagotchiId>)>),
+}
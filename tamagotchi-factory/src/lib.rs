@@ -0,0 +1,181 @@
+#![no_std]
+
+use gstd::{collections::HashMap, msg, prelude::*, prog::ProgramGenerator, ActorId, CodeId};
+use tamagotchi_factory_io::*;
+use tamagotchi_io::{Offspring, TmgInit};
+
+#[derive(Debug, Clone, Default)]
+struct Factory {
+    tamagotchi_code_id: CodeId,
+    /// The tamagotchis made by the factory by their current owner,
+    /// kept up to date by the tamagotchis on transfer.
+    tamagotchis: HashMap<ActorId, Vec<TamagotchiId>>,
+}
+
+static mut FACTORY: Option<Factory> = None;
+
+impl Factory {
+    /// Creates a tamagotchi owned by the sender
+    /// Arguments:
+    /// `name`: the tamagotchi name
+    async fn create_tamagotchi(&mut self, name: String) -> Result<FactoryEvent, FactoryError> {
+        let owner = msg::source();
+
+        let tamagotchi_id = self
+            .create(
+                owner,
+                TmgInit {
+                    name,
+                    config: None,
+                    owner: Some(owner),
+                    offspring: None,
+                },
+            )
+            .await?;
+
+        Ok(FactoryEvent::TamagotchiCreated {
+            tamagotchi_id,
+            owner,
+        })
+    }
+
+    /// Creates the offspring of two tamagotchis made by the factory for `owner`
+    /// Arguments:
+    /// `name`: the offspring name
+    /// `owner`: the owner of the offspring
+    /// `offspring`: the parents and what the offspring inherits from them,
+    /// the first parent must be the sender
+    async fn create_offspring(
+        &mut self,
+        name: String,
+        owner: ActorId,
+        offspring: Offspring,
+    ) -> Result<FactoryEvent, FactoryError> {
+        let parents = offspring.parents;
+        if parents.0 != msg::source() || !self.is_known(&parents.0) || !self.is_known(&parents.1) {
+            return Err(FactoryError::UnknownParents);
+        }
+
+        let tamagotchi_id = self
+            .create(
+                owner,
+                TmgInit {
+                    name,
+                    config: None,
+                    owner: Some(owner),
+                    offspring: Some(offspring),
+                },
+            )
+            .await?;
+
+        Ok(FactoryEvent::OffspringCreated {
+            tamagotchi_id,
+            owner,
+            parents,
+        })
+    }
+
+    /// Creates a tamagotchi for `owner` and registers it
+    /// The tamagotchi is owned by `owner` from its init, so there's no transfer that could fail
+    async fn create(
+        &mut self,
+        owner: ActorId,
+        init: TmgInit,
+    ) -> Result<TamagotchiId, FactoryError> {
+        let (tamagotchi_id, _) =
+            ProgramGenerator::create_program_for_reply(self.tamagotchi_code_id, init, 0, 0)
+                .map_err(|_| FactoryError::CreationFailed)?
+                .await
+                .map_err(|_| FactoryError::CreationFailed)?;
+
+        self.tamagotchis
+            .entry(owner)
+            .or_default()
+            .push(tamagotchi_id);
+
+        Ok(tamagotchi_id)
+    }
+
+    /// Moves the sender, a tamagotchi made by the factory, to `new_owner` in the registry
+    /// Arguments:
+    /// `new_owner`: the owner the tamagotchi was transferred to
+    fn transferred(&mut self, new_owner: ActorId) -> Result<FactoryEvent, FactoryError> {
+        let tamagotchi_id = msg::source();
+        let tamagotchis = self
+            .tamagotchis
+            .values_mut()
+            .find(|tamagotchis| tamagotchis.contains(&tamagotchi_id))
+            .ok_or(FactoryError::UnknownTamagotchi)?;
+        tamagotchis.retain(|id| *id != tamagotchi_id);
+        self.tamagotchis
+            .retain(|_, tamagotchis| !tamagotchis.is_empty());
+
+        self.tamagotchis
+            .entry(new_owner)
+            .or_default()
+            .push(tamagotchi_id);
+
+        Ok(FactoryEvent::Transferred {
+            tamagotchi_id,
+            owner: new_owner,
+        })
+    }
+
+    /// Checks that the tamagotchi was made by the factory.
+    fn is_known(&self, tamagotchi_id: &TamagotchiId) -> bool {
+        self.tamagotchis
+            .values()
+            .any(|tamagotchis| tamagotchis.contains(tamagotchi_id))
+    }
+}
+
+#[gstd::async_main]
+async fn main() {
+    let factory = unsafe { FACTORY.get_or_insert(Factory::default()) };
+
+    let action: FactoryAction = msg::load().expect("Could not load Action");
+
+    let result = match action {
+        FactoryAction::CreateTamagotchi { name } => factory.create_tamagotchi(name).await,
+        FactoryAction::CreateOffspring {
+            name,
+            owner,
+            offspring,
+        } => factory.create_offspring(name, owner, offspring).await,
+        FactoryAction::Transferred(new_owner) => factory.transferred(new_owner),
+    };
+
+    msg::reply(result, 0)
+        .expect("Failed to encode or reply with `Result<FactoryEvent, FactoryError>`");
+}
+
+#[no_mangle]
+extern fn init() {
+    let InitFactory { tamagotchi_code_id } = msg::load().expect("Unable to decode InitFactory");
+
+    unsafe {
+        FACTORY = Some(Factory {
+            tamagotchi_code_id,
+            ..Default::default()
+        })
+    };
+}
+
+#[no_mangle]
+extern fn state() {
+    let factory = unsafe { FACTORY.take().expect("Unexpected error in taking state") };
+    let query: FactoryStateQuery = msg::load().expect("Unable to decode `FactoryStateQuery`");
+
+    let reply = match query {
+        FactoryStateQuery::TamagotchiCodeId => {
+            FactoryStateReply::TamagotchiCodeId(factory.tamagotchi_code_id)
+        }
+        FactoryStateQuery::Tamagotchis(owner) => FactoryStateReply::Tamagotchis(
+            factory.tamagotchis.get(&owner).cloned().unwrap_or_default(),
+        ),
+        FactoryStateQuery::All => FactoryStateReply::All(factory.tamagotchis.into_iter().collect()),
+    };
+
+    msg::reply(reply, 0)
+        .expect("Failed to encode or reply with `FactoryStateReply` from `state()`");
+}
@@ -0,0 +1,140 @@
+use gstd::{ActorId, Encode};
+use gtest::System;
+use tamagotchi_factory_io::*;
+use tamagotchi_io::{TmgAction, TmgError, TmgReply, Traits};
+
+#[path = "../../tamagotchi/tests/utils/mod.rs"]
+mod utils;
+use utils::{full_state, init_factory, tamagotchis};
+
+const OWNER: u64 = 3;
+const NEW_OWNER: u64 = 4;
+
+#[test]
+fn create_tamagotchi() {
+    let sys = System::new();
+    sys.init_logger();
+    let factory = init_factory(&sys, OWNER);
+
+    for name in ["Tama", "Gotchi"] {
+        let res = factory.send(
+            OWNER,
+            FactoryAction::CreateTamagotchi {
+                name: String::from(name),
+            },
+        );
+        assert!(!res.main_failed());
+    }
+
+    let created = tamagotchis(&factory, OWNER);
+    assert_eq!(created.len(), 2);
+
+    for (tamagotchi_id, name) in created.iter().zip(["Tama", "Gotchi"]) {
+        let id: [u8; 32] = (*tamagotchi_id).into();
+        let tmg = sys.get_program(id);
+
+        let res = tmg.send(OWNER, TmgAction::Name);
+        assert!(res.contains(&(
+            OWNER,
+            Ok::<TmgReply, TmgError>(TmgReply::Name(String::from(name))).encode()
+        )));
+    }
+
+    let state: FactoryStateReply = factory
+        .read_state(FactoryStateQuery::All)
+        .expect("Can't read state");
+    assert_eq!(
+        state,
+        FactoryStateReply::All(vec![(ActorId::from(OWNER), created)])
+    );
+}
+
+#[test]
+fn create_event() {
+    let sys = System::new();
+    sys.init_logger();
+    let factory = init_factory(&sys, OWNER);
+
+    let res = factory.send(
+        OWNER,
+        FactoryAction::CreateTamagotchi {
+            name: String::from("Tama"),
+        },
+    );
+    let tamagotchi_id = tamagotchis(&factory, OWNER)[0];
+
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<FactoryEvent, FactoryError>(FactoryEvent::TamagotchiCreated {
+            tamagotchi_id,
+            owner: OWNER.into(),
+        })
+        .encode()
+    )));
+    assert!(tamagotchis(&factory, OWNER + 1).is_empty());
+}
+
+#[test]
+fn transfer() {
+    let sys = System::new();
+    sys.init_logger();
+    let factory = init_factory(&sys, OWNER);
+
+    let res = factory.send(
+        OWNER,
+        FactoryAction::CreateTamagotchi {
+            name: String::from("Tama"),
+        },
+    );
+    assert!(!res.main_failed());
+    let tamagotchi_id = tamagotchis(&factory, OWNER)[0];
+    let id: [u8; 32] = tamagotchi_id.into();
+    let tmg = sys.get_program(id);
+
+    // The tamagotchi is owned by the sender from its init.
+    let res = tmg.send(OWNER, TmgAction::Transfer(NEW_OWNER.into()));
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::Transferred(NEW_OWNER.into())).encode()
+    )));
+
+    // The tamagotchi tells the factory, which moves it to the new owner.
+    assert!(tamagotchis(&factory, OWNER).is_empty());
+    assert_eq!(tamagotchis(&factory, NEW_OWNER), vec![tamagotchi_id]);
+
+    let res = factory.send(OWNER, FactoryAction::Transferred(OWNER.into()));
+    assert!(res.contains(&(
+        OWNER,
+        Err::<FactoryEvent, FactoryError>(FactoryError::UnknownTamagotchi).encode()
+    )));
+    assert_eq!(tamagotchis(&factory, NEW_OWNER), vec![tamagotchi_id]);
+}
+
+#[test]
+fn traits() {
+    let sys = System::new();
+    sys.init_logger();
+    let factory = init_factory(&sys, OWNER);
+
+    let traits: Vec<Traits> = [OWNER, NEW_OWNER]
+        .into_iter()
+        .map(|owner| {
+            let res = factory.send(
+                owner,
+                FactoryAction::CreateTamagotchi {
+                    name: String::from("Tama"),
+                },
+            );
+            assert!(!res.main_failed());
+
+            let id: [u8; 32] = tamagotchis(&factory, owner)[0].into();
+            let state = full_state(&sys.get_program(id));
+            assert_eq!(state.traits, Traits::new(&owner.into(), "Tama"));
+
+            state.traits
+        })
+        .collect();
+
+    // The traits are seeded with the real owner, not the factory.
+    assert_ne!(traits[0], traits[1]);
+}
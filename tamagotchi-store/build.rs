@@ -0,0 +1,5 @@
+use tamagotchi_store_io::StoreMetadata;
+
+fn main() {
+    gear_wasm_builder::build_with_metadata::<StoreMetadata>();
+}
@@ -0,0 +1,89 @@
+#![no_std]
+
+use gmeta::{In, InOut, Metadata, Out};
+use gstd::{prelude::*, ActorId};
+pub use tamagotchi_io::AttributeId;
+
+pub type Price = u128;
+pub type TamagotchiId = ActorId;
+
+pub struct StoreMetadata;
+
+impl Metadata for StoreMetadata {
+    type Init = In<InitStore>;
+    type Handle = InOut<StoreAction, Result<StoreEvent, StoreError>>;
+    type Others = ();
+    type Reply = ();
+    type Signal = ();
+    type State = Out<IoStore>;
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct InitStore {
+    pub ft_contract: ActorId,
+}
+
+#[derive(Debug, Clone, Default, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct AttributeMetadata {
+    pub title: String,
+    pub description: String,
+    pub media: String,
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum StoreAction {
+    /// Puts a new attribute on sale. Allowed only for the admin.
+    CreateAttribute {
+        attribute_id: AttributeId,
+        metadata: AttributeMetadata,
+        price: Price,
+    },
+    /// Sells the attribute to the sender, which must have approved
+    /// the store to transfer `price` fungible tokens.
+    BuyAttribute {
+        attribute_id: AttributeId,
+    },
+    GetAttributes(TamagotchiId),
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum StoreEvent {
+    AttributeCreated(AttributeId),
+    AttributeSold {
+        attribute_id: AttributeId,
+        buyer: TamagotchiId,
+        price: Price,
+    },
+    Attributes(Vec<AttributeId>),
+}
+
+#[derive(Debug, Clone, Decode, Encode, TypeInfo, PartialEq, Eq)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub enum StoreError {
+    NotAdmin,
+    AttributeExists,
+    AttributeNotFound,
+    AlreadyOwned,
+    /// The buyer is already paying for the attribute.
+    PurchasePending,
+    PaymentFailed,
+}
+
+#[derive(Debug, Clone, Default, Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct IoStore {
+    pub admin: ActorId,
+    pub ft_contract: ActorId,
+    pub attributes: Vec<(AttributeId, (AttributeMetadata, Price))>,
+    pub owners: Vec<(TamagotchiId, Vec<AttributeId>)>,
+}
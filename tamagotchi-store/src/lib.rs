@@ -0,0 +1,171 @@
+#![no_std]
+
+use fungible_token_io::{FTAction, FTError, FTEvent};
+use gstd::{
+    collections::{HashMap, HashSet},
+    exec, msg,
+    prelude::*,
+    ActorId,
+};
+use tamagotchi_store_io::*;
+
+#[derive(Debug, Clone, Default)]
+struct AttributeStore {
+    admin: ActorId,
+    ft_contract: ActorId,
+    attributes: HashMap<AttributeId, (AttributeMetadata, Price)>,
+    owners: HashMap<TamagotchiId, Vec<AttributeId>>,
+    /// Purchases waiting for the payment, a second one of the same attribute is refused.
+    pending: HashSet<(TamagotchiId, AttributeId)>,
+}
+
+static mut STORE: Option<AttributeStore> = None;
+
+impl AttributeStore {
+    /// Puts a new attribute on sale
+    /// Arguments:
+    /// `attribute_id`: the attribute identifier
+    /// `metadata`: the attribute description
+    /// `price`: the price in fungible tokens
+    fn create_attribute(
+        &mut self,
+        attribute_id: AttributeId,
+        metadata: AttributeMetadata,
+        price: Price,
+    ) -> Result<StoreEvent, StoreError> {
+        if msg::source() != self.admin {
+            return Err(StoreError::NotAdmin);
+        }
+
+        if self.attributes.contains_key(&attribute_id) {
+            return Err(StoreError::AttributeExists);
+        }
+
+        self.attributes.insert(attribute_id, (metadata, price));
+
+        Ok(StoreEvent::AttributeCreated(attribute_id))
+    }
+
+    /// Sells the attribute to the sender, paying with its fungible tokens
+    /// The attribute is recorded only after the payment succeeds,
+    /// while it's pending the buyer can't pay for the same attribute again
+    /// Arguments:
+    /// `attribute_id`: the attribute identifier
+    async fn buy_attribute(&mut self, attribute_id: AttributeId) -> Result<StoreEvent, StoreError> {
+        let buyer = msg::source();
+
+        let (_, price) = self
+            .attributes
+            .get(&attribute_id)
+            .ok_or(StoreError::AttributeNotFound)?;
+        let price = *price;
+
+        if self
+            .owners
+            .get(&buyer)
+            .is_some_and(|attributes| attributes.contains(&attribute_id))
+        {
+            return Err(StoreError::AlreadyOwned);
+        }
+
+        if !self.pending.insert((buyer, attribute_id)) {
+            return Err(StoreError::PurchasePending);
+        }
+        let payment = self.pay(buyer, price).await;
+        self.pending.remove(&(buyer, attribute_id));
+        payment?;
+
+        self.owners.entry(buyer).or_default().push(attribute_id);
+
+        Ok(StoreEvent::AttributeSold {
+            attribute_id,
+            buyer,
+            price,
+        })
+    }
+
+    /// Transfers `price` tokens of `buyer` to the store
+    async fn pay(&self, buyer: ActorId, price: Price) -> Result<(), StoreError> {
+        msg::send_for_reply_as::<_, Result<FTEvent, FTError>>(
+            self.ft_contract,
+            FTAction::Transfer {
+                from: buyer,
+                to: exec::program_id(),
+                amount: price,
+            },
+            0,
+            0,
+        )
+        .map_err(|_| StoreError::PaymentFailed)?
+        .await
+        .map_err(|_| StoreError::PaymentFailed)?
+        .map_err(|_| StoreError::PaymentFailed)?;
+
+        Ok(())
+    }
+
+    fn attributes(&self, tamagotchi_id: &TamagotchiId) -> StoreEvent {
+        StoreEvent::Attributes(self.owners.get(tamagotchi_id).cloned().unwrap_or_default())
+    }
+}
+
+#[gstd::async_main]
+async fn main() {
+    let store = unsafe { STORE.get_or_insert(AttributeStore::default()) };
+
+    let action: StoreAction = msg::load().expect("Could not load Action");
+
+    let result = match action {
+        StoreAction::CreateAttribute {
+            attribute_id,
+            metadata,
+            price,
+        } => store.create_attribute(attribute_id, metadata, price),
+        StoreAction::BuyAttribute { attribute_id } => store.buy_attribute(attribute_id).await,
+        StoreAction::GetAttributes(tamagotchi_id) => Ok(store.attributes(&tamagotchi_id)),
+    };
+
+    msg::reply(result, 0).expect("Failed to encode or reply with `Result<StoreEvent, StoreError>`");
+}
+
+#[no_mangle]
+extern fn init() {
+    let InitStore { ft_contract } = msg::load().expect("Unable to decode InitStore");
+
+    unsafe {
+        STORE = Some(AttributeStore {
+            admin: msg::source(),
+            ft_contract,
+            ..Default::default()
+        })
+    };
+}
+
+#[no_mangle]
+extern fn state() {
+    let store = unsafe { STORE.take().expect("Unexpected error in taking state") };
+    msg::reply::<IoStore>(store.into(), 0)
+        .expect("Failed to encode or reply with `IoStore` from `state()`");
+}
+
+impl From<AttributeStore> for IoStore {
+    fn from(value: AttributeStore) -> Self {
+        let AttributeStore {
+            admin,
+            ft_contract,
+            attributes,
+            owners,
+            ..
+        } = value;
+
+        let attributes = attributes.into_iter().collect();
+        let owners = owners.into_iter().collect();
+
+        Self {
+            admin,
+            ft_contract,
+            attributes,
+            owners,
+        }
+    }
+}
@@ -1,69 +1,20 @@
 #![no_std]
 
-/*
-use gear_lib::non_fungible_token::{
-    io::{NFTApproval, NFTTransfer, NFTTransferPayout},
-    delegated::DelegatedApproveMessage,
-    royalties::*,
-    token::{TokenId, TokenMetadata},
-};
-*/
-use gmeta::{In, Out, InOut, Metadata};
-use gstd::{prelude::*, ActorId, collections::BTreeMap, msg, errors::Error, exec};
-/*
-use gear_lib::non_fungible_token::token::{
-    TokenMetadata,
-    TokenId
-}
-use gear_lib::non_fungible_token::io::{
-    NFTTransfer,
-    NFTApproval,
-    NFTTransferPayout
-};
-use gear_lib::non_fungible_token::royalties::Payout;
-use gear_lib::non_fungible_token::delegated::DelegatedApproveMessage;
-*/
-
-use primitive_types::H256;
-use codec::{Decode, Encode};
-
-pub type NftContractId = ActorId;
-pub type UserId = ActorId;
-pub type MatchId = ActorId;
-pub type TransactionId = u64;
-pub type GameId = usize;
-pub type InGame = bool;
-pub type NFTPrice = u128;
-
+use core::ops::RangeInclusive;
+use gmeta::{In, InOut, Metadata, Out};
+use gstd::{prelude::*, ActorId};
 
 pub type AttributeId = u32;
 
-
-/*
-pub struct ProgramMetadata;
-
-
-
-impl Metadata for ProgramMetadata {
-    type Init = In<InitContractData>;
-    type Handle = InOut<RutzoAction, RutzoEvent>;
-    type Others = InOut<RutzoAction, RutzoEvent>;
-    type Signal = ();
-    type Reply = ();    
-    type State = 
-}
-*/
-
-
 pub struct ProgramMetadata;
 
 impl Metadata for ProgramMetadata {
     type Init = In<TmgInit>;
-    type Handle = InOut<TmgAction, TmgReply>;
+    type Handle = InOut<TmgAction, Result<TmgReply, TmgError>>;
     type Reply = ();
-    type Others = ();
+    type Others = Out<TmgReply>;
     type Signal = ();
-    type State = Out<Tamagotchi>;//InOut<RutzoStateAction, RutzoStateEvent>; 
+    type State = InOut<TmgStateQuery, TmgStateReply>;
 }
 
 #[derive(Debug, Clone, Encode, Decode, TypeInfo)]
@@ -71,6 +22,49 @@ impl Metadata for ProgramMetadata {
 #[scale_info(crate = gstd::scale_info)]
 pub struct TmgInit {
     pub name: String,
+    /// Falls back to [`TmgConfig::default`] if not set.
+    pub config: Option<TmgConfig>,
+    /// Set by a factory creating the tamagotchi for someone else,
+    /// the deployer becomes the tamagotchi's factory. Falls back to the deployer if not set.
+    pub owner: Option<ActorId>,
+    /// Set by the factory for a bred tamagotchi, requires `owner`
+    /// so that the factory is recorded as the one vouching for the genealogy.
+    pub offspring: Option<Offspring>,
+}
+
+/// Inheritance of a bred tamagotchi.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct Offspring {
+    pub parents: (ActorId, ActorId),
+    pub traits: Traits,
+    pub generation: u32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct Genealogy {
+    /// `None` for a tamagotchi that wasn't bred.
+    pub parents: Option<(ActorId, ActorId)>,
+    pub generation: u32,
+}
+
+/// Decay and fill parameters of the needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+#[codec(crate = gstd::codec)]
+#[scale_info(crate = gstd::scale_info)]
+pub struct TmgConfig {
+    pub hunger_per_block: u64,
+    pub boredom_per_block: u64,
+    pub energy_per_block: u64,
+    pub fill_per_feed: u64,
+    pub fill_per_entertainment: u64,
+    pub fill_per_sleep: u64,
+    pub max_value: u64,
+    /// Duration in ms of the block the decay rates refer to.
+    pub block_duration: u64,
 }
 
 #[derive(Encode, Decode, TypeInfo, Debug)]
@@ -83,757 +77,631 @@ pub enum TmgAction {
     Play,
     Sleep,
     TmgInfo,
+    Transfer(ActorId),
+    Approve(ActorId),
+    RevokeApproval,
+    /// Reserves gas that pays for the auto-care checks.
+    ReserveGas {
+        amount: u64,
+        duration: u32,
+    },
+    /// Sets the care `actions` performed every `interval` blocks,
+    /// empty `actions` disable the auto-care.
+    SetAutoCare {
+        interval: u32,
+        actions: Vec<CareAction>,
+    },
+    /// Sent by the program to itself to perform the auto-care.
+    CheckState,
+    SetFTokenContract(ActorId),
+    /// Allows `account` to spend `amount` fungible tokens of the tamagotchi.
+    ApproveTokens {
+        account: ActorId,
+        amount: u128,
+    },
+    /// Buys the attribute in the store, which must be approved to spend its price.
+    BuyAttribute {
+        store_id: ActorId,
+        attribute_id: AttributeId,
+    },
+    /// Replies with the current needs and the purchased attributes.
+    Stats,
+    /// Brings a dead tamagotchi back as a baby, the message must carry [`REVIVE_COST`].
+    Revive,
+    /// Replaces the config, which must pass [`TmgConfig::validate`].
+    UpdateConfig(TmgConfig),
+    Mood,
+    /// Sets the factory that creates the offspring and is told about transfers,
+    /// once it's set (by the init of a factory-made tamagotchi too) it can't be changed.
+    SetFactory(ActorId),
+    /// Allows `partner` to breed with the tamagotchi once.
+    ApproveBreeding(ActorId),
+    /// Breeds the adult tamagotchi with an adult `partner` that approved it,
+    /// the offspring named `name` goes to the owner.
+    Breed {
+        partner: ActorId,
+        name: String,
+    },
+    /// Sent by the tamagotchi that breeds with this one to get its consent.
+    ConfirmBreeding,
+    /// Sent by the same tamagotchi when its factory fails to create the offspring,
+    /// gives the consent and the cooldown back.
+    CancelBreeding,
 }
 
-#[derive(Encode, Debug, PartialEq, Eq, Decode, TypeInfo)]
+#[derive(Encode, Decode, TypeInfo, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub enum TmgReply {
-    Name(String),
-    Age(u64),
-    Fed,
-    Entertained,
-    Slept,
-    TmgInfo {
-        owner: ActorId,
-        name: String,
-        date_of_birth: u64,
-    },
+pub enum LifeStage {
+    #[default]
+    Egg,
+    Baby,
+    Adult,
+    Elder,
+    Dead,
 }
 
-#[derive(Default, Encode, Decode, TypeInfo)]
+#[derive(Encode, Decode, TypeInfo, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub struct Tamagotchi {
-    pub name: String,
-    pub date_of_birth: u64,
-    pub owner: ActorId,
-    pub fed: u64,
-    pub fed_block: u64,
-    pub entertained: u64,
-    pub entertained_block: u64,
-    pub rested: u64,
-    pub rested_block: u64,
-    pub allowed_account: Option<ActorId>,
+pub enum CareAction {
+    Feed,
+    Play,
+    Sleep,
 }
 
-#[derive(Encode, Decode, TypeInfo)]
+#[derive(Encode, Decode, TypeInfo, Debug, Clone, PartialEq, Eq)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub struct TmgCurrentState {
-    pub fed: u64,
-    pub entertained: u64,
-    pub rested: u64,
+pub struct AutoCare {
+    pub interval: u32,
+    pub actions: Vec<CareAction>,
 }
 
-pub const HUNGER_PER_BLOCK: u64 = 1;
-pub const BOREDOM_PER_BLOCK: u64 = 2;
-pub const ENERGY_PER_BLOCK: u64 = 2;
-
-pub const FILL_PER_FEED: u64 = 2_000;
-pub const FILL_PER_ENTERTAINMENT: u64 = 2_000;
-pub const FILL_PER_SLEEP: u64 = 2_000;
-
-pub const MAX_VALUE: u64 = 10_000;
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-/*
-Change all enums and structs with this configuration
-
-
-#[derive(Encode, Decode, TypeInfo)]
+#[derive(Encode, Decode, TypeInfo, Debug, Clone, PartialEq, Eq)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub enum PingStateReply {
-    All(Vec<String>),
-    Text(String),
-    Number(u64)
+pub struct CareRecord {
+    pub action: CareAction,
+    /// The program itself for the auto-care.
+    pub actor: ActorId,
+    pub timestamp: u64,
 }
-*/
 
-/*
-#[derive(Encode, Decode, TypeInfo)]
+#[derive(Encode, Decode, TypeInfo, Debug, Default, Clone, PartialEq, Eq)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub enum RutzoStateAction {
-    GetId,
-    Story,
-    All
+pub struct CareStats {
+    pub total_feeds: u64,
+    pub total_plays: u64,
+    pub total_sleeps: u64,
+    /// Longest time in ms without any need hitting zero among the finished streaks.
+    pub longest_streak: u64,
+    /// Start of the current streak.
+    pub streak_start: u64,
+    /// Sum of the care quality at every care.
+    pub need_level_sum: u128,
 }
 
-#[derive(Encode, Decode, TypeInfo)]
+/// [`CareStats`] at a given block.
+#[derive(Encode, Decode, TypeInfo, Debug, Default, Clone, PartialEq, Eq)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub enum RutzoStateEvent {
-    Id(UserId),
-    StoryEvent(String),
-    AllEvent(Tamagotchi)
+pub struct CareSummary {
+    pub total_feeds: u64,
+    pub total_plays: u64,
+    pub total_sleeps: u64,
+    pub longest_streak: u64,
+    pub current_streak: u64,
+    /// Average care quality at the time of care.
+    pub average_need: u64,
 }
-*/
-
-/*
 
-#[derive(Encode, Decode, TypeInfo)]
+#[derive(Encode, Debug, PartialEq, Eq, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub enum RutzoAction {
-    PlayGame {
-        token_id: TokenId,
-        power: String
+pub enum TmgReply {
+    Name(String),
+    Age(u64),
+    /// The care replies carry the mood after the care.
+    Fed(Mood),
+    Entertained(Mood),
+    Slept(Mood),
+    TmgInfo {
+        owner: ActorId,
+        name: String,
+        date_of_birth: u64,
+    },
+    Transferred(ActorId),
+    Approved(ActorId),
+    ApprovalRevoked,
+    GasReserved,
+    AutoCareSet,
+    /// Sent to the owner when only the given number of gas reservations is left.
+    ReservationLow(u32),
+    FTokenContractSet,
+    TokensApproved {
+        account: ActorId,
+        amount: u128,
     },
-    MintCard {
-        token_id: u8
+    AttributePurchased(AttributeId),
+    Stats {
+        fed: u64,
+        entertained: u64,
+        rested: u64,
+        attributes: Vec<AttributeId>,
     },
-    SetNFTAddress(ActorId),
-    Register,
-    AddNftForSale {
-        token_metadata: TokenMetadata
+    /// Sent to the owner when the tamagotchi reaches a new stage.
+    Evolved(LifeStage),
+    Revived,
+    ConfigUpdated,
+    Mood(Mood),
+    FactorySet,
+    BreedingApproved(ActorId),
+    /// The consent of the partner with what the offspring inherits from it.
+    BreedingConfirmed {
+        traits: Traits,
+        generation: u32,
     },
-    BuyNFT(TokenId),
-    ApproveMinter(ActorId),
-    DelegateApprovedUser(ActorId)
+    Bred(ActorId),
+    BreedingCancelled,
 }
 
-#[derive(Encode, Decode, TypeInfo)]
+#[derive(Encode, Debug, PartialEq, Eq, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub enum RutzoEvent {
-    Minted(TokenId),
-    NFTContractSaved,
-    ErrorCallingNFTContract,
-    UserIsNotApproved(UserId),
-    UserIsNotTheOwner(UserId),
-    Approved(UserId),
-    UserApprovedNotExists(UserId),
-    ApprovedUserDeleted(UserId),
-    ErrorBuying(String),
-    InsufficientFunds(NFTPrice),
-    NftWithTokenIdDoesNotExists(TokenId),
-    NFTWithIdNotExists(u8),
-    AccountAlreadyExist(UserId),
-    AccountNotExists(UserId),
-    AccountAlreadyInMatch(UserId),
-    QueryNotAllowed(String),
-    RegisterSucces,
-    LoginSucces,
-    UserInMatch(MatchId),
-    ErrorInJoiningMatch,
-    PurchaseSucces,
-    ReplySuccess,
-    NewPlayer(UserId),
-    MatchFinished,
-    MatchCreated,
-    UserIsAlreadyInAGame(u64),
-    PendingTransfer(TokenId),
-    TransferSuccess(TokenId),
-    NFTIsNotApprovedByMainContract(TokenId),
-    MaxMintsReached(UserId)
+pub enum TmgError {
+    Dead,
+    NotOwner,
+    /// The tamagotchi isn't stored, the runtime always runs the init first, so this is
+    /// only a guard and can't be replied in a test.
+    NotInitialized,
+    Cooldown,
+    /// A need was updated later than the given block or a fill overflows.
+    ///
+    /// Only off-chain callers of [`Tamagotchi::current_state`] can get it: the program's
+    /// blocks only grow and its fills are capped by `max_value`, so it never replies it.
+    Overflow,
+    ZeroInterval,
+    NoGasReserved,
+    Reservation,
+    FTokenContractNotSet,
+    /// The fungible token contract failed or rejected the approval.
+    TokensApproval,
+    /// The store failed or rejected the purchase.
+    Purchase,
+    NotDead,
+    InsufficientValue,
+    InvalidConfig,
+    NotAdult,
+    BreedingCooldown,
+    /// The partner failed or refused to breed.
+    BreedingRefused,
+    FactoryNotSet,
+    /// The factory vouching for the genealogy can't be replaced.
+    FactoryAlreadySet,
+    /// The factory failed to create the offspring.
+    Breeding,
 }
 
-#[derive(Encode, Decode, TypeInfo)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub enum GameState {
-    WaitigForPlayer,
-    GameInProgress
+pub struct Tamagotchi {
+    pub name: String,
+    pub date_of_birth: u64,
+    pub owner: ActorId,
+    pub fed: u64,
+    pub fed_block: u64,
+    pub entertained: u64,
+    pub entertained_block: u64,
+    pub rested: u64,
+    pub rested_block: u64,
+    pub allowed_account: Option<ActorId>,
+    pub reservations: u32,
+    pub auto_care: Option<AutoCare>,
+    pub ft_contract_id: Option<ActorId>,
+    pub attributes: Vec<AttributeId>,
+    pub stage: LifeStage,
+    pub config: TmgConfig,
+    /// The last [`HISTORY_LEN`] cares, oldest first.
+    pub history: Vec<CareRecord>,
+    pub care_stats: CareStats,
+    pub traits: Traits,
+    pub factory_id: Option<ActorId>,
+    pub breeding_approval: Option<ActorId>,
+    pub last_bred: Option<u64>,
+    pub genealogy: Genealogy,
 }
 
-#[derive(Encode, Decode, TypeInfo, Default, Clone)]
+#[derive(Encode, Decode, TypeInfo, Debug, Clone, Copy, PartialEq, Eq)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub struct UserDataState {
-    current_game: Option<u64>,
-    past_games: Vec<u64>,
+pub enum TmgStateQuery {
+    Full,
+    /// The needs and the stage at the current block.
+    CurrentState,
+    Owner,
+    Stage,
+    Mood,
 }
 
-impl From<UserGameData> for UserDataState {
-    fn from(value: UserGameData) -> Self {
-        let current_game = if let Some(game_id) = value.current_game {
-            Some(game_id as u64)
-        } else {
-            None  
-        };
-        let past_games = value.past_games
-            .iter()
-            .map(|game_id| *game_id as u64)
-            .collect();
-        Self {
-            current_game,
-            past_games,
-        }
-    }
-}
-
-#[derive(Encode, Decode, TypeInfo, Default, Clone)]
+#[derive(Encode, Decode, TypeInfo, Debug, Clone, PartialEq, Eq)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub struct UserDefaultMints {
-    nfts_minted: Vec<u8>,
-    can_mint: bool
+pub enum TmgStateReply {
+    Full(Tamagotchi),
+    CurrentState(TmgCurrentState),
+    Owner {
+        owner: ActorId,
+        allowed_account: Option<ActorId>,
+    },
+    Stage(LifeStage),
+    Mood(Mood),
 }
 
-#[derive(Encode, Decode, TypeInfo)]
+#[derive(Encode, Decode, TypeInfo, Debug, Clone, Copy, PartialEq, Eq)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub struct ContractState {
-    pub owner: ActorId,
-    pub nft_contract: Option<NftContractId>,
-    pub games: Vec<MatchInformation>,
-    pub games_waiting: Vec<u64>,
-    pub games_information_by_user: Vec<(UserId, UserDataState)>,
-    pub game_id: u64,
-    pub tokens_metadata_default: Vec<(u8, TokenMetadata)>,
-    pub nfts_for_sale: Vec<(TokenId, NFTPrice)>,
-    pub default_tokens_minted_by_id: Vec<(UserId, UserDefaultMints)>,
-    pub approved_minters: Vec<UserId>,
-    pub transaction_id: TransactionId,
-    pub pending_transfers: Vec<(UserId, (UserId, TokenId))>
+pub enum Mood {
+    Happy,
+    Hungry,
+    Bored,
+    Tired,
+    /// A need is empty.
+    Sick,
 }
 
-#[derive(Encode, Decode, TypeInfo)]
+/// Personality of a tamagotchi, every trait is a percentage of the matching decay rate.
+#[derive(Encode, Decode, TypeInfo, Debug, Default, Clone, Copy, PartialEq, Eq)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub struct InitContractData {
-    pub nft_contract: Option<ActorId>,
-    pub tokens_metadata_default: Vec<TokenMetadata>,
+pub struct Traits {
+    /// Scales the hunger.
+    pub appetite: u8,
+    /// Scales the boredom.
+    pub playfulness: u8,
+    /// Scales the energy loss.
+    pub sleepiness: u8,
 }
 
-#[derive(Encode, Decode, TypeInfo, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
 #[codec(crate = gstd::codec)]
 #[scale_info(crate = gstd::scale_info)]
-pub struct UserData {
-    pub user_id: ActorId,
-    pub chosen_nft: TokenId,
-    pub power: u8
+pub struct TmgCurrentState {
+    pub fed: u64,
+    pub entertained: u64,
+    pub rested: u64,
+    pub stage: LifeStage,
 }
 
-#[derive(Encode, Decode, TypeInfo, Default, Clone)]
-#[codec(crate = gstd::codec)]
-#[scale_info(crate = gstd::scale_info)]
-pub enum MatchState {
-    Finished {
-        winner: ActorId,
-        loser: ActorId
-    },
-    #[default]
-    InProgress,
-    NotExists
-}
+// Defaults of `TmgConfig`.
+pub const HUNGER_PER_BLOCK: u64 = 1;
+pub const BOREDOM_PER_BLOCK: u64 = 2;
+pub const ENERGY_PER_BLOCK: u64 = 2;
 
-#[derive(Encode, Decode, TypeInfo)]
-#[codec(crate = gstd::codec)]
-#[scale_info(crate = gstd::scale_info)]
-pub struct MatchInformation {
-    pub user_1: UserData,
-    pub user_2: Option<UserData>,
-    pub match_state: MatchState,
-}
+pub const FILL_PER_FEED: u64 = 2_000;
+pub const FILL_PER_ENTERTAINMENT: u64 = 2_000;
+pub const FILL_PER_SLEEP: u64 = 2_000;
 
-#[derive(Default, Clone)]
-pub struct UserGameData {
-    current_game: Option<usize>,
-    past_games: Vec<usize>,
+pub const MAX_VALUE: u64 = 10_000;
+pub const BLOCK_DURATION: u64 = 1_000;
+
+// Bounds of `TmgConfig`, fills must also not exceed `max_value`.
+pub const DECAY_RANGE: RangeInclusive<u64> = 1..=100;
+pub const MAX_VALUE_RANGE: RangeInclusive<u64> = 1_000..=1_000_000;
+pub const BLOCK_DURATION_RANGE: RangeInclusive<u64> = 1..=60_000;
+
+/// Minimum time in ms between two identical care actions.
+pub const CARE_COOLDOWN: u64 = 10_000;
+
+/// Auto-care fills a need once it drops below this value.
+pub const AUTO_CARE_THRESHOLD: u64 = 3_000;
+/// The owner is notified once this many gas reservations or fewer are left.
+pub const LOW_RESERVATIONS: usize = 1;
+
+/// Number of the last cares kept in the history.
+pub const HISTORY_LEN: usize = 64;
+
+/// Age in ms at which the egg hatches.
+pub const HATCH_AGE: u64 = 60_000;
+/// Age in ms from which a well cared baby grows up.
+pub const ADULT_AGE: u64 = 600_000;
+/// Age in ms at which an adult becomes an elder.
+pub const ELDER_AGE: u64 = 3_600_000;
+/// Value that must be attached to [`TmgAction::Revive`].
+pub const REVIVE_COST: u128 = 1_000_000_000_000;
+
+/// Minimum time in ms between two breedings of a tamagotchi.
+pub const BREEDING_COOLDOWN: u64 = 600_000;
+
+/// Bounds of every trait in percent.
+pub const TRAIT_RANGE: RangeInclusive<u8> = 50..=150;
+/// Seed of the hash the traits are derived from.
+pub const TRAITS_SEED: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+impl Default for TmgConfig {
+    fn default() -> Self {
+        Self {
+            hunger_per_block: HUNGER_PER_BLOCK,
+            boredom_per_block: BOREDOM_PER_BLOCK,
+            energy_per_block: ENERGY_PER_BLOCK,
+            fill_per_feed: FILL_PER_FEED,
+            fill_per_entertainment: FILL_PER_ENTERTAINMENT,
+            fill_per_sleep: FILL_PER_SLEEP,
+            max_value: MAX_VALUE,
+            block_duration: BLOCK_DURATION,
+        }
+    }
 }
 
-#[derive(Default)]
-pub struct Contract {
-    pub owner: ActorId,
-    pub nft_contract: Option<NftContractId>,
-    pub games: Vec<MatchInformation>,
-    pub games_waiting: Vec<GameId>,
-    pub games_information_by_user: BTreeMap<UserId, UserGameData>,
-    pub game_id: GameId,
-    pub tokens_metadata_default: BTreeMap<u8, TokenMetadata>,
-    pub nfts_for_sale: BTreeMap<TokenId, NFTPrice>,
-    pub default_tokens_minted_by_id: BTreeMap<UserId, UserDefaultMints>,
-    pub approved_minters: Vec<UserId>,
-    pub transaction_id: TransactionId,
-    pub pending_transfers: BTreeMap<UserId, (UserId, TokenId)>
-}
+impl TmgConfig {
+    /// Checks that every parameter is within its bounds.
+    pub fn validate(&self) -> Result<(), TmgError> {
+        let decays = [
+            self.hunger_per_block,
+            self.boredom_per_block,
+            self.energy_per_block,
+        ];
+        let fills = [
+            self.fill_per_feed,
+            self.fill_per_entertainment,
+            self.fill_per_sleep,
+        ];
+        if !MAX_VALUE_RANGE.contains(&self.max_value)
+            || !BLOCK_DURATION_RANGE.contains(&self.block_duration)
+            || !decays.iter().all(|decay| DECAY_RANGE.contains(decay))
+            || !fills.iter().all(|fill| (1..=self.max_value).contains(fill))
+        {
+            return Err(TmgError::InvalidConfig);
+        }
+        Ok(())
+    }
 
-impl Contract {
-    pub async fn mint_new_nft(nft_contract: ActorId, token_metadata: TokenMetadata, transaction_id: u64) -> Result<NFTEvent, Error> {
-        msg::send_for_reply_as::<NFTAction, NFTEvent>(
-            nft_contract, 
-            NFTAction::Mint { 
-                transaction_id, 
-                token_metadata
-            }, 
-            0, 
-            0
-        )
-        .expect("Error in sending a message 'NFTAction::Mint' to nft contract")
-        .await
+    /// Returns the value of a need at `now`, given that it was `value` at `updated_at`
+    /// and that it decays by `decay_per_block` every `block_duration`.
+    ///
+    /// Returns `None` if `now` is earlier than `updated_at`.
+    pub fn current_need(
+        &self,
+        value: u64,
+        updated_at: u64,
+        now: u64,
+        decay_per_block: u64,
+    ) -> Option<u64> {
+        let blocks = now
+            .checked_sub(updated_at)?
+            .checked_div(self.block_duration)?;
+        Some(value.saturating_sub(decay_per_block.saturating_mul(blocks)))
     }
-    
-    pub async fn transfer_nft(nft_contract: ActorId, to: ActorId, token_id: TokenId, transaction_id: u64) -> Result<NFTEvent, ()>{
-        match msg::send_for_reply_as::<NFTAction, NFTEvent>(
-            nft_contract, 
-            NFTAction::Transfer { 
-                transaction_id, 
-                to, 
-                token_id
-            }, 
-            0, 
-            0
-        )
-        .expect("Error in sending a message 'NFTAction::Transfer' to nft contract")
-        .await {
-            Ok(nft_event) => {
-                let NFTEvent::Transfer(_) = nft_event else { 
-                    panic!("Unexpected answer from nft contract"); 
-                };
-                Ok(nft_event)
-            },
-            Err(_) => Err(())
+
+    /// Returns the time at which a need that was `value` at `updated_at` hits zero.
+    pub fn zero_at(&self, value: u64, updated_at: u64, decay_per_block: u64) -> u64 {
+        if decay_per_block == 0 {
+            return u64::MAX;
         }
+        value
+            .div_ceil(decay_per_block)
+            .saturating_mul(self.block_duration)
+            .saturating_add(updated_at)
     }
-    
-    pub async fn main_contract_is_approved(nft_contract: ActorId, main_contract: ActorId, token_id: TokenId) -> Result<bool, ()> {
-        match msg::send_for_reply_as::<NFTAction, NFTEvent>(
-            nft_contract, 
-            NFTAction::IsApproved { 
-                to: main_contract,
-                token_id 
-            }, 
-            0, 
-            0
-        )
-        .expect("Error in sending a message 'NFTAction::MintFromMainContract' to a match contract")
-        .await {
-            Ok(nft_event) => {
-                let NFTEvent::IsApproved { approved, .. } = nft_event else { 
-                    panic!("Unexpected answer from nft contract"); 
-                };
-                Ok(approved)
-            },
-            Err(_) => Err(())
-        }
+
+    /// Returns the value of a need after adding `fill` to its current value at `now`,
+    /// clamped to `max_value`.
+    ///
+    /// Returns `None` if `now` is earlier than `updated_at` or the addition overflows.
+    pub fn fill_need(
+        &self,
+        value: u64,
+        updated_at: u64,
+        now: u64,
+        decay_per_block: u64,
+        fill: u64,
+    ) -> Option<u64> {
+        self.current_need(value, updated_at, now, decay_per_block)?
+            .checked_add(fill)
+            .map(|value| value.min(self.max_value))
     }
-}
 
-impl Contract {
-    pub async fn buy_nft(&mut self, user_id: UserId, token_id: TokenId, value: NFTPrice) -> (RutzoEvent, u128) {
-        if !self.is_register(&user_id) {
-            return (RutzoEvent::AccountNotExists(user_id), value);
-        }
-        
-        let token_on_sale_value = match self.nfts_for_sale.get(&token_id) {
-            Some(value) =>* value,
-            None => return (RutzoEvent::NftWithTokenIdDoesNotExists(token_id), value)
-        };
-        
-        if value < token_on_sale_value {
-            return (RutzoEvent::InsufficientFunds(value), value);
+    /// Returns the mood driven by the lowest need,
+    /// the tamagotchi is happy while all of them are at least half of `max_value`
+    /// and sick once one of them is empty.
+    pub fn mood(&self, fed: u64, entertained: u64, rested: u64) -> Mood {
+        let lowest = fed.min(entertained).min(rested);
+        if lowest == 0 {
+            Mood::Sick
+        } else if lowest >= self.max_value / 2 {
+            Mood::Happy
+        } else if lowest == fed {
+            Mood::Hungry
+        } else if lowest == entertained {
+            Mood::Bored
+        } else {
+            Mood::Tired
         }
-        
-        let value_to_return = value - token_on_sale_value;
-        
-        Contract::transfer_nft(
-            self.nft_contract.unwrap(), 
-            user_id, 
-            token_id, 
-            self.transaction_id
-        )
-        .await
-        .expect("Unable to decode NFTEvent");
-        
-        (RutzoEvent::NFTContractSaved, value_to_return)
     }
-    
-    pub async fn mint_nft_to_sale(&mut self, user_id: ActorId, token_metadata: TokenMetadata, value: NFTPrice) -> RutzoEvent {
-        let user_approved = self.approved_minters 
-            .iter()
-            .find(|&approved_user_id| *approved_user_id == user_id);
-        
-        if user_approved.is_none() && self.owner != user_id {
-            return RutzoEvent::UserIsNotApproved(user_id);
+
+    /// Returns the config with the decay rates scaled by `traits`,
+    /// a need still decays by at least 1 per block.
+    pub fn with_traits(&self, traits: &Traits) -> Self {
+        let scale = |decay: u64, percent: u8| (decay.saturating_mul(percent.into()) / 100).max(1);
+        Self {
+            hunger_per_block: scale(self.hunger_per_block, traits.appetite),
+            boredom_per_block: scale(self.boredom_per_block, traits.playfulness),
+            energy_per_block: scale(self.energy_per_block, traits.sleepiness),
+            ..*self
         }
-        
-        let answer = Contract::mint_new_nft(
-            self.nft_contract.unwrap(), 
-            token_metadata, 
-            self.transaction_id
-        )
-        .await
-        .expect("Unable to decode NFTEvent");
-        
-        let NFTEvent::Transfer(NFTTransfer { token_id, .. }) = answer else {
-            panic!("Unable to decode NFTEvent");  
-        };
-        
-        self.nfts_for_sale.insert(token_id, value);
-        self.transaction_id = self.transaction_id.saturating_add(1);
-        
-        RutzoEvent::Minted(token_id)
     }
-    
-    pub async fn mint_card(&mut self, token_id: u8) -> RutzoEvent {
-        let user_id = msg::source();
-        
-        if !self.is_register(&user_id) {
-            return RutzoEvent::AccountNotExists(user_id);
-        } 
-        
-        let nfts_minted_data = self.default_tokens_minted_by_id.get_mut(&user_id).unwrap();
-        
-        if nfts_minted_data.can_mint && nfts_minted_data.nfts_minted.len() == 3 {
-            nfts_minted_data.can_mint = false;
-            return RutzoEvent::MaxMintsReached(user_id);    
+
+    /// Returns the stage a tamagotchi at `stage` reaches at `age` with the given needs.
+    /// A baby grows up only if the average of its needs is at least half of `max_value`.
+    ///
+    /// A tamagotchi dies once all of its needs are empty and stays dead until revived.
+    pub fn life_stage(
+        &self,
+        stage: LifeStage,
+        age: u64,
+        fed: u64,
+        entertained: u64,
+        rested: u64,
+    ) -> LifeStage {
+        if fed == 0 && entertained == 0 && rested == 0 {
+            return LifeStage::Dead;
         }
-    
-        let token_metadata = self.tokens_metadata_default
-            .get(&token_id);
-           
-        let token_metadata = match token_metadata {
-            Some(token_data) => token_data.clone(),
-            None => return RutzoEvent::NFTWithIdNotExists(token_id)
-        };
-        
-        let answer = Contract::mint_new_nft(
-            self.nft_contract.unwrap(), 
-            token_metadata, 
-            self.transaction_id
-        )
-        .await
-        .expect("Unable to decode NFTEvent");
-        
-        let NFTEvent::Transfer(transfer_data) = answer else {
-            return RutzoEvent::ErrorCallingNFTContract; 
-        };
-        
-        self.transaction_id = self.transaction_id.saturating_add(1);
-        
-        Contract::transfer_nft(
-            self.nft_contract.unwrap(),
-            user_id, 
-            transfer_data.token_id,
-            self.transaction_id
-        )
-        .await
-        .expect("Unable to decode NFTEvent");
-        
-        self.default_tokens_minted_by_id
-            .entry(user_id)
-            .and_modify(|minted_data| minted_data.nfts_minted.push(token_id))
-            .or_default();
-        
-        self.transaction_id = self.transaction_id.saturating_add(1);
-        
-        RutzoEvent::Minted(TokenId::default())
-    }
-    
-    pub fn add_minter(&mut self, user_id: ActorId) -> RutzoEvent {
-        let caller = msg::source();
-        if caller != self.owner {
-            return RutzoEvent::UserIsNotTheOwner(caller);
+        let good_care = self.max_value / 2;
+        let mut stage = stage;
+        loop {
+            stage = match stage {
+                LifeStage::Egg if age >= HATCH_AGE => LifeStage::Baby,
+                LifeStage::Baby
+                    if age >= ADULT_AGE && care_quality(fed, entertained, rested) >= good_care =>
+                {
+                    LifeStage::Adult
+                }
+                LifeStage::Adult if age >= ELDER_AGE => LifeStage::Elder,
+                _ => return stage,
+            };
         }
-        
-        self.approved_minters.push(user_id);
-        
-        RutzoEvent::Approved(user_id)
     }
-    
-    pub fn register_user(&mut self, user_id: UserId) -> RutzoEvent {
-        if self.is_register(&user_id) {
-            return RutzoEvent::AccountAlreadyExist(user_id);
+}
+
+impl Traits {
+    /// Derives the traits from the hash of `owner` and `name` seeded with [`TRAITS_SEED`].
+    pub fn new(owner: &ActorId, name: &str) -> Self {
+        let hash = owner
+            .as_ref()
+            .iter()
+            .chain(name.as_bytes())
+            .fold(TRAITS_SEED, |hash, byte| {
+                (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+            });
+        let span = u64::from(TRAIT_RANGE.end() - TRAIT_RANGE.start()) + 1;
+        let trait_at = |shift: u32| TRAIT_RANGE.start() + ((hash >> shift) % span) as u8;
+        Self {
+            appetite: trait_at(0),
+            playfulness: trait_at(16),
+            sleepiness: trait_at(32),
         }
-      
-        self.games_information_by_user.insert(user_id, Default::default());       
-        self.default_tokens_minted_by_id.insert(user_id, Default::default());
-        RutzoEvent::RegisterSucces
     }
-    
-    pub fn is_register(&self, user_id: &UserId) ->  bool {
-        self.games_information_by_user.contains_key(user_id)
-    }
-    
-    pub async fn play_game(&mut self, user_id: ActorId, token_id: TokenId, power: u8) -> RutzoEvent {
-        if !self.is_register(&user_id) {    
-            return RutzoEvent::AccountNotExists(user_id);
-        }
-        
-        let user_data = self.games_information_by_user
-            .get_mut(&user_id)
-            .unwrap();
-            
-        if let Ok(approved) = Contract::main_contract_is_approved(
-            self.nft_contract.unwrap().clone(), 
-            exec::program_id(),
-            token_id
-        ).await {
-            if !approved {
-                return RutzoEvent::NFTIsNotApprovedByMainContract(token_id);
-            }
-        } else {
-            return RutzoEvent::NFTIsNotApprovedByMainContract(token_id);
-        }
-        
-        if let Some(&(to, token_id)) = self.pending_transfers.get(&user_id) {
-            if Contract::transfer_nft(
-                self.nft_contract.unwrap(),
-                to, 
-                token_id,
-                self.transaction_id
-            ).await.is_err() {
-                return RutzoEvent::PendingTransfer(token_id);
-            }
-            
-            self.transaction_id = self.transaction_id.saturating_add(1);
-            self.pending_transfers.remove(&user_id);
-            
-            return RutzoEvent::TransferSuccess(token_id);
-        }
-        
-        if user_data.current_game.is_some() {
-            return RutzoEvent::UserIsAlreadyInAGame(
-                user_data.current_game.unwrap() as u64
-            );
+
+    /// Returns the traits of an offspring, the average of both parents.
+    pub fn inherit(&self, partner: &Traits) -> Self {
+        let average = |first: u8, second: u8| ((u16::from(first) + u16::from(second)) / 2) as u8;
+        Self {
+            appetite: average(self.appetite, partner.appetite),
+            playfulness: average(self.playfulness, partner.playfulness),
+            sleepiness: average(self.sleepiness, partner.sleepiness),
         }
-        
-        let (game_data, game_id) = match self.games_waiting.pop() {
-            None => {
-                self.games.push(MatchInformation {
-                    user_1: UserData {
-                        user_id,
-                        chosen_nft: token_id,
-                        power
-                    },
-                    user_2: None,
-                    match_state: MatchState::default()
-                });
-                self.games_waiting.push(self.game_id);
-                self.set_player_in_current_game(user_id, self.game_id);
-                self.game_id = self.game_id.saturating_add(1);
-                
-                return RutzoEvent::MatchCreated;
-            },
-            Some(game_id) => {                
-                (&mut self.games[game_id], game_id)
-            }
-        };
-        
-        game_data.user_2 = Some(UserData {
-            user_id,
-            chosen_nft: token_id,
-            power,
-        });
-        
-        let user1_power = game_data.user_1.power;
-        let user1_id = game_data.user_1.user_id;
-        
-        let (winner, loser, token_id) = if user1_power > power {
-            let winner = user1_id;
-            let loser = user_id;
-            let token_id = token_id;
-            (winner, loser, token_id)
-        } else {
-            let winner = user_id;
-            let loser = user1_id;
-            let token_id = game_data.user_1.chosen_nft;
-            (winner, loser, token_id)
-        };
-                
-        game_data.match_state = MatchState::Finished { 
-            winner, 
-            loser
+    }
+}
+
+impl Tamagotchi {
+    /// Returns the needs and the stage at `now`, the program and the metawasm both derive
+    /// them here.
+    ///
+    /// Returns [`TmgError::Overflow`] if `now` is earlier than a need was updated.
+    pub fn current_state(&self, now: u64) -> Result<TmgCurrentState, TmgError> {
+        let config = self.decay_config();
+        let need = |value, updated_at, decay_per_block| {
+            config
+                .current_need(value, updated_at, now, decay_per_block)
+                .ok_or(TmgError::Overflow)
         };
-        
-        self.add_past_game_to_player(user_id, game_id);
-        self.add_past_game_to_player(user1_id, game_id);
-        self.delete_player_current_game(winner);
-        self.delete_player_current_game(loser);
-                
-        if Contract::transfer_nft(
-            self.nft_contract.unwrap(),
-            winner, 
-            token_id,
-            self.transaction_id
-        ).await.is_err() {
-            self.pending_transfers.insert(loser, (winner, token_id));
-            return RutzoEvent::PendingTransfer(token_id);
-        }
-        
-        self.transaction_id = self.transaction_id.saturating_add(1);
-        
-        RutzoEvent::MatchFinished
+        let fed = need(self.fed, self.fed_block, config.hunger_per_block)?;
+        let entertained = need(
+            self.entertained,
+            self.entertained_block,
+            config.boredom_per_block,
+        )?;
+        let rested = need(self.rested, self.rested_block, config.energy_per_block)?;
+        Ok(TmgCurrentState {
+            fed,
+            entertained,
+            rested,
+            stage: self.config.life_stage(
+                self.stage,
+                now.saturating_sub(self.date_of_birth),
+                fed,
+                entertained,
+                rested,
+            ),
+        })
     }
-    
-    pub fn set_player_in_current_game(&mut self, user_id: ActorId, game_id: usize) {
-        self.games_information_by_user
-            .entry(user_id)
-            .and_modify(|user_game_data| {
-                user_game_data.current_game = Some(game_id);
-            })
-            .or_default();
+
+    /// Returns the config with the decay rates of the tamagotchi.
+    pub fn decay_config(&self) -> TmgConfig {
+        self.config.with_traits(&self.traits)
     }
-    
-    pub fn add_past_game_to_player(&mut self, user_id: ActorId, game_id: usize) {
-        self.games_information_by_user
-            .entry(user_id)
-            .and_modify(|user_game_data| {
-                user_game_data.past_games.push(game_id);
-            })
-            .or_default();
+
+    /// Returns the time at which the first need hits zero.
+    pub fn first_zero(&self) -> u64 {
+        let config = self.decay_config();
+        config
+            .zero_at(self.fed, self.fed_block, config.hunger_per_block)
+            .min(config.zero_at(
+                self.entertained,
+                self.entertained_block,
+                config.boredom_per_block,
+            ))
+            .min(config.zero_at(self.rested, self.rested_block, config.energy_per_block))
+    }
+
+    /// Ends the current care streak if a need has hit zero by `now`,
+    /// must be called before the needs are changed.
+    pub fn update_streak(&mut self, now: u64) {
+        let first_zero = self.first_zero();
+        self.care_stats.update_streak(first_zero, now);
+    }
+
+    /// Returns the care stats at `now`.
+    pub fn care_summary(&self, now: u64) -> CareSummary {
+        self.care_stats.summary(self.first_zero(), now)
     }
-    
-    pub fn delete_player_current_game(&mut self, user_id: ActorId) {
-        self.games_information_by_user
-            .entry(user_id)
-            .and_modify(|user_game_data| {
-                user_game_data.current_game = None;
-            })
-            .or_default();
-    }    
 }
 
-impl From<Contract> for ContractState {
-    fn from(value: Contract) -> Self {
-        let Contract {
-            owner,
-            nft_contract,
-            games_information_by_user,
-            games_waiting,
-            games,
-            game_id,
-            pending_transfers,
-            default_tokens_minted_by_id,
-            nfts_for_sale,
-            tokens_metadata_default,
-            approved_minters,
-            transaction_id,
-        } = value;
-
-        let games_waiting = games_waiting
-            .iter()
-            .map(|match_id| *match_id as u64)
-            .collect();
-        let tokens_metadata_default = tokens_metadata_default
-            .iter()
-            .map(|(token_id, token_metadata)| (*token_id, token_metadata.clone()))
-            .collect();
-        let default_tokens_minted_by_id = default_tokens_minted_by_id
-            .iter()
-            .map(|(user_id, minted)| (*user_id, minted.clone()))
-            .collect();
-        let games_information_by_user = games_information_by_user
-            .iter()
-            .map(|(user_id, user_data)| (*user_id, user_data.clone().into()))
-            .collect();
-        let pending_transfers = pending_transfers
-            .iter()
-            .map(|(user_id, token_id)| (*user_id, *token_id))
-            .collect();
-        let nfts_for_sale = nfts_for_sale 
-            .iter()
-            .map(|(token_id, price)| (*token_id, *price) )
-            .collect();
-        let game_id = game_id as u64;
-        
-        Self {
-            owner,
-            nft_contract,
-            games,
-            games_waiting,
-            games_information_by_user,
-            game_id,
-            tokens_metadata_default,
-            nfts_for_sale,
-            default_tokens_minted_by_id,
-            approved_minters,
-            transaction_id,
-            pending_transfers
+impl CareStats {
+    pub fn total_cares(&self) -> u64 {
+        self.total_feeds
+            .saturating_add(self.total_plays)
+            .saturating_add(self.total_sleeps)
+    }
+
+    /// Ends the current streak if a need has hit zero at `first_zero` by `now`,
+    /// the next one starts at `now`.
+    pub fn update_streak(&mut self, first_zero: u64, now: u64) {
+        if first_zero <= now {
+            self.longest_streak = self
+                .longest_streak
+                .max(first_zero.saturating_sub(self.streak_start));
+            self.streak_start = now;
         }
     }
-}
 
-#[derive(Debug, Encode, Decode, PartialEq, Eq, PartialOrd, Ord, Clone, TypeInfo, Hash)]
-#[codec(crate = gstd::codec)]
-#[scale_info(crate = gstd::scale_info)]
-pub enum NFTAction {
-    Mint {
-        transaction_id: u64,
-        token_metadata: TokenMetadata,
-    },
-    Burn {
-        transaction_id: u64,
-        token_id: TokenId,
-    },
-    Transfer {
-        transaction_id: u64,
-        to: ActorId,
-        token_id: TokenId,
-    },
-    TransferPayout {
-        transaction_id: u64,
-        to: ActorId,
-        token_id: TokenId,
-        amount: u128,
-    },
-    NFTPayout {
-        owner: ActorId,
-        amount: u128,
-    },
-    Approve {
-        transaction_id: u64,
-        to: ActorId,
-        token_id: TokenId,
-    },
-    DelegatedApprove {
-        transaction_id: u64,
-        message: DelegatedApproveMessage,
-        signature: [u8; 64],
-    },
-    Owner {
-        token_id: TokenId,
-    },
-    IsApproved {
-        to: ActorId,
-        token_id: TokenId,
-    },
-    Clear {
-        transaction_hash: H256,
-    },
-    AddMinter {
-        transaction_id: u64,
-        minter_id: ActorId,
-    },
+    /// Returns the stats at `now`, given that the first need hits zero at `first_zero`.
+    pub fn summary(&self, first_zero: u64, now: u64) -> CareSummary {
+        let current_streak = if first_zero <= now {
+            0
+        } else {
+            now.saturating_sub(self.streak_start)
+        };
+        let finished_streak = first_zero.min(now).saturating_sub(self.streak_start);
+        let average_need = self
+            .need_level_sum
+            .checked_div(self.total_cares() as u128)
+            .unwrap_or_default() as u64;
+        CareSummary {
+            total_feeds: self.total_feeds,
+            total_plays: self.total_plays,
+            total_sleeps: self.total_sleeps,
+            longest_streak: self.longest_streak.max(finished_streak),
+            current_streak,
+            average_need,
+        }
+    }
 }
 
-#[derive(Debug, Encode, Decode, PartialEq, Eq, PartialOrd, Ord, Clone, TypeInfo, Hash)]
-#[codec(crate = gstd::codec)]
-#[scale_info(crate = gstd::scale_info)]
-pub enum NFTEvent {
-    Transfer(NFTTransfer),
-    TransferPayout(NFTTransferPayout),
-    NFTPayout(Payout),
-    Approval(NFTApproval),
-    Owner {
-        owner: ActorId,
-        token_id: TokenId,
-    },
-    IsApproved {
-        to: ActorId,
-        token_id: TokenId,
-        approved: bool,
-    },
-    MinterAdded {
-        minter_id: ActorId,
-    },
+/// Returns the average of the needs.
+pub fn care_quality(fed: u64, entertained: u64, rested: u64) -> u64 {
+    ((fed as u128 + entertained as u128 + rested as u128) / 3) as u64
 }
-
-*/
\ No newline at end of file
@@ -0,0 +1,128 @@
+use proptest::prelude::*;
+use tamagotchi_io::*;
+
+fn config() -> impl Strategy<Value = TmgConfig> {
+    (MAX_VALUE_RANGE, BLOCK_DURATION_RANGE).prop_flat_map(|(max_value, block_duration)| {
+        (
+            [DECAY_RANGE, DECAY_RANGE, DECAY_RANGE],
+            [1..=max_value, 1..=max_value, 1..=max_value],
+        )
+            .prop_map(
+                move |([hunger, boredom, energy], [feed, entertainment, sleep])| TmgConfig {
+                    hunger_per_block: hunger,
+                    boredom_per_block: boredom,
+                    energy_per_block: energy,
+                    fill_per_feed: feed,
+                    fill_per_entertainment: entertainment,
+                    fill_per_sleep: sleep,
+                    max_value,
+                    block_duration,
+                },
+            )
+    })
+}
+
+proptest! {
+    #[test]
+    fn bounded_config_is_valid(config in config()) {
+        prop_assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn current_need_never_grows(
+        config in config(),
+        value in 0..=MAX_VALUE,
+        updated_at in any::<u64>(),
+        elapsed in any::<u64>(),
+        decay_per_block in 0..=MAX_VALUE,
+    ) {
+        let now = updated_at.saturating_add(elapsed);
+        let current = config.current_need(value, updated_at, now, decay_per_block).unwrap();
+
+        prop_assert!(current <= value);
+    }
+
+    #[test]
+    fn current_need_is_monotonic(
+        config in config(),
+        value in 0..=MAX_VALUE,
+        updated_at in any::<u64>(),
+        first in any::<u64>(),
+        second in any::<u64>(),
+        decay_per_block in 0..=MAX_VALUE,
+    ) {
+        let earlier = updated_at.saturating_add(first.min(second));
+        let later = updated_at.saturating_add(first.max(second));
+        let need = |now| config.current_need(value, updated_at, now, decay_per_block).unwrap();
+
+        prop_assert!(need(later) <= need(earlier));
+    }
+
+    #[test]
+    fn fill_need_is_clamped(
+        config in config(),
+        value in 0..=MAX_VALUE,
+        updated_at in any::<u64>(),
+        elapsed in any::<u64>(),
+        decay_per_block in 0..=MAX_VALUE,
+        fill in 0..=MAX_VALUE,
+    ) {
+        let now = updated_at.saturating_add(elapsed);
+        let current = config.current_need(value, updated_at, now, decay_per_block).unwrap();
+        let filled = config.fill_need(value, updated_at, now, decay_per_block, fill).unwrap();
+
+        prop_assert!(filled <= config.max_value);
+        prop_assert_eq!(filled, (current + fill).min(config.max_value));
+    }
+
+    #[test]
+    fn past_timestamp_is_rejected(
+        config in config(),
+        value in 0..=MAX_VALUE,
+        updated_at in 1..=u64::MAX,
+        now in any::<u64>(),
+        decay_per_block in any::<u64>(),
+        fill in any::<u64>(),
+    ) {
+        prop_assume!(now < updated_at);
+
+        prop_assert_eq!(config.current_need(value, updated_at, now, decay_per_block), None);
+        prop_assert_eq!(config.fill_need(value, updated_at, now, decay_per_block, fill), None);
+    }
+
+    #[test]
+    fn current_state_rejects_past_timestamp(
+        updated_at in 1..=u64::MAX,
+        now in any::<u64>(),
+    ) {
+        prop_assume!(now < updated_at);
+        let state = Tamagotchi {
+            fed_block: updated_at,
+            entertained_block: updated_at,
+            rested_block: updated_at,
+            ..Default::default()
+        };
+
+        prop_assert_eq!(state.current_state(now), Err(TmgError::Overflow));
+        prop_assert!(state.current_state(updated_at).is_ok());
+    }
+
+    #[test]
+    fn fill_overflow_is_rejected(
+        config in config(),
+        value in 1..=u64::MAX,
+        now in any::<u64>(),
+    ) {
+        prop_assert_eq!(config.fill_need(value, now, now, 0, u64::MAX), None);
+    }
+
+    #[test]
+    fn out_of_bounds_config_is_rejected(
+        config in config(),
+        decay in (*DECAY_RANGE.end() + 1)..=u64::MAX,
+    ) {
+        let config = TmgConfig { hunger_per_block: decay, ..config };
+
+        prop_assert_eq!(config.validate(), Err(TmgError::InvalidConfig));
+    }
+}
@@ -0,0 +1,62 @@
+use proptest::prelude::*;
+use tamagotchi_io::*;
+
+fn life_stage(stage: LifeStage, age: u64, fed: u64, entertained: u64, rested: u64) -> LifeStage {
+    TmgConfig::default().life_stage(stage, age, fed, entertained, rested)
+}
+
+fn stage() -> impl Strategy<Value = LifeStage> {
+    prop_oneof![
+        Just(LifeStage::Egg),
+        Just(LifeStage::Baby),
+        Just(LifeStage::Adult),
+        Just(LifeStage::Elder),
+        Just(LifeStage::Dead),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn stage_never_goes_back(
+        stage in stage(),
+        age in any::<u64>(),
+        fed in 0..=MAX_VALUE,
+        entertained in 0..=MAX_VALUE,
+        rested in 0..=MAX_VALUE,
+    ) {
+        prop_assert!(life_stage(stage, age, fed, entertained, rested) >= stage);
+    }
+
+    #[test]
+    fn dead_stays_dead(
+        age in any::<u64>(),
+        fed in 0..=MAX_VALUE,
+        entertained in 0..=MAX_VALUE,
+        rested in 0..=MAX_VALUE,
+    ) {
+        prop_assert_eq!(
+            life_stage(LifeStage::Dead, age, fed, entertained, rested),
+            LifeStage::Dead
+        );
+    }
+
+    #[test]
+    fn empty_needs_kill(stage in stage(), age in any::<u64>()) {
+        prop_assert_eq!(life_stage(stage, age, 0, 0, 0), LifeStage::Dead);
+    }
+
+    #[test]
+    fn poor_care_keeps_baby(
+        age in any::<u64>(),
+        fed in 0..MAX_VALUE / 2,
+        entertained in 0..MAX_VALUE / 2,
+        rested in 0..MAX_VALUE / 2,
+    ) {
+        prop_assume!(fed + entertained + rested > 0);
+
+        prop_assert_eq!(
+            life_stage(LifeStage::Baby, age, fed, entertained, rested),
+            LifeStage::Baby
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use gstd::ActorId;
+use proptest::prelude::*;
+use tamagotchi_io::*;
+
+fn traits() -> impl Strategy<Value = Traits> {
+    [TRAIT_RANGE, TRAIT_RANGE, TRAIT_RANGE].prop_map(|[appetite, playfulness, sleepiness]| Traits {
+        appetite,
+        playfulness,
+        sleepiness,
+    })
+}
+
+proptest! {
+    #[test]
+    fn traits_are_bounded(owner in any::<[u8; 32]>(), name in ".*") {
+        let traits = Traits::new(&ActorId::new(owner), &name);
+
+        prop_assert!(TRAIT_RANGE.contains(&traits.appetite));
+        prop_assert!(TRAIT_RANGE.contains(&traits.playfulness));
+        prop_assert!(TRAIT_RANGE.contains(&traits.sleepiness));
+        prop_assert_eq!(Traits::new(&ActorId::new(owner), &name), traits);
+    }
+
+    #[test]
+    fn traits_scale_decay(traits in traits(), decay in DECAY_RANGE) {
+        let config = TmgConfig {
+            hunger_per_block: decay,
+            ..Default::default()
+        };
+        let scaled = config.with_traits(&traits).hunger_per_block;
+
+        prop_assert!(scaled >= 1);
+        prop_assert!(scaled >= decay / 2 && scaled <= decay * 3 / 2);
+        prop_assert_eq!(config.with_traits(&traits).fill_per_feed, config.fill_per_feed);
+    }
+
+    #[test]
+    fn empty_need_makes_sick(
+        fed in 0..=MAX_VALUE,
+        entertained in 0..=MAX_VALUE,
+        rested in 0..=MAX_VALUE,
+    ) {
+        let mood = TmgConfig::default().mood(fed, entertained, rested);
+
+        prop_assert_eq!(mood == Mood::Sick, fed == 0 || entertained == 0 || rested == 0);
+    }
+}
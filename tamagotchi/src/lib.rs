@@ -1,149 +1,718 @@
 #![no_std]
 
-use codec::{Decode, Encode};
-use gstd::{exec, msg, prelude::*, ActorId};
+use fungible_token_io::{FTAction, FTError, FTEvent};
+use gstd::{collections::BTreeMap, exec, msg, prelude::*, ActorId, ReservationId};
+use tamagotchi_factory_io::{FactoryAction, FactoryError, FactoryEvent};
 use tamagotchi_io::*;
+use tamagotchi_store_io::{StoreAction, StoreError, StoreEvent};
 
-#[derive(Default, Encode, Decode, TypeInfo)]
-#[codec(crate = gstd::codec)]
-#[scale_info(crate = gstd::scale_info)]
+#[derive(Default)]
 struct Tamagotchi {
-    name: String,
-    date_of_birth: u64,
-    owner: ActorId,
-    fed: u64,
-    fed_block: u64,
-    entertained: u64,
-    entertained_block: u64,
-    rested: u64,
-    rested_block: u64,
+    /// Everything the state shows, the needs and the stage are derived from it
+    /// by [`tamagotchi_io::Tamagotchi::current_state`].
+    state: tamagotchi_io::Tamagotchi,
+    reservations: Vec<Reservation>,
+    auto_care_scheduled: bool,
+    /// When each need was last cared for by the owner or the approved account,
+    /// the care cooldowns start from it.
+    cared_at: BTreeMap<CareAction, u64>,
+    /// The partner the last breeding consent went to and `last_bred` before it.
+    confirmed_breeding: Option<(ActorId, Option<u64>)>,
+}
+
+struct Reservation {
+    id: ReservationId,
+    expires_at: u32,
 }
 
 static mut TAMAGOTCHI: Option<Tamagotchi> = None;
 
 impl Tamagotchi {
-    fn feed(&mut self) {
-        assert!(!self.tmg_is_dead(), "Tamagotchi has died");
-        self.fed += FILL_PER_FEED - self.calculate_hunger();
-        self.fed_block = exec::block_timestamp();
-        self.fed = if self.fed > MAX_VALUE {
-            MAX_VALUE
-        } else {
-            self.fed
+    fn care(&mut self, action: CareAction) -> Result<TmgReply, TmgError> {
+        self.check_care(action)?;
+        self.fill(action, msg::source())?;
+        self.cared_at.insert(action, exec::block_timestamp());
+        let mood = self.mood()?;
+        Ok(match action {
+            CareAction::Feed => TmgReply::Fed(mood),
+            CareAction::Play => TmgReply::Entertained(mood),
+            CareAction::Sleep => TmgReply::Slept(mood),
+        })
+    }
+
+    /// Fills the need that `action` takes care of and records the care made by `actor`.
+    fn fill(&mut self, action: CareAction, actor: ActorId) -> Result<(), TmgError> {
+        let now = exec::block_timestamp();
+        let TmgCurrentState {
+            fed,
+            entertained,
+            rested,
+            ..
+        } = self.current_state()?;
+        self.update_streak();
+        let config = self.state.decay_config();
+        let state = &mut self.state;
+        let (value, updated_at, decay_per_block, fill) = match action {
+            CareAction::Feed => (
+                &mut state.fed,
+                &mut state.fed_block,
+                config.hunger_per_block,
+                config.fill_per_feed,
+            ),
+            CareAction::Play => (
+                &mut state.entertained,
+                &mut state.entertained_block,
+                config.boredom_per_block,
+                config.fill_per_entertainment,
+            ),
+            CareAction::Sleep => (
+                &mut state.rested,
+                &mut state.rested_block,
+                config.energy_per_block,
+                config.fill_per_sleep,
+            ),
         };
-        msg::reply(TmgReply::Fed, 0).expect("Error in a reply `TmgEvent::Fed`");
+        *value = config
+            .fill_need(*value, *updated_at, now, decay_per_block, fill)
+            .ok_or(TmgError::Overflow)?;
+        *updated_at = now;
+
+        if state.history.len() == HISTORY_LEN {
+            state.history.remove(0);
+        }
+        state.history.push(CareRecord {
+            action,
+            actor,
+            timestamp: now,
+        });
+        let stats = &mut state.care_stats;
+        let total = match action {
+            CareAction::Feed => &mut stats.total_feeds,
+            CareAction::Play => &mut stats.total_plays,
+            CareAction::Sleep => &mut stats.total_sleeps,
+        };
+        *total = total.saturating_add(1);
+        stats.need_level_sum = stats
+            .need_level_sum
+            .saturating_add(care_quality(fed, entertained, rested).into());
+        Ok(())
+    }
+
+    /// Must be called before the needs are changed.
+    fn update_streak(&mut self) {
+        self.state.update_streak(exec::block_timestamp());
+    }
+
+    /// Checks that the sender may take care of the tamagotchi
+    /// and that `action` is not on cooldown.
+    fn check_care(&self, action: CareAction) -> Result<(), TmgError> {
+        if !self.is_owner_or_approved(&msg::source()) {
+            return Err(TmgError::NotOwner);
+        }
+        if self.tmg_is_dead()? {
+            return Err(TmgError::Dead);
+        }
+        if self.cared_at.get(&action).is_some_and(|cared_at| {
+            exec::block_timestamp().saturating_sub(*cared_at) < CARE_COOLDOWN
+        }) {
+            return Err(TmgError::Cooldown);
+        }
+        Ok(())
+    }
+
+    fn current_state(&self) -> Result<TmgCurrentState, TmgError> {
+        self.state.current_state(exec::block_timestamp())
     }
 
-    fn play(&mut self) {
-        assert!(!self.tmg_is_dead(), "Tamagotchi has died");
-        self.entertained += FILL_PER_ENTERTAINMENT - self.calculate_boredom();
-        self.entertained_block = exec::block_timestamp();
-        self.entertained = if self.entertained > MAX_VALUE {
-            MAX_VALUE
-        } else {
-            self.entertained
+    /// Stores the stage reached since the last message and notifies the owner about it.
+    fn update_stage(&mut self) {
+        let Ok(TmgCurrentState { stage, .. }) = self.current_state() else {
+            return;
         };
-        msg::reply(TmgReply::Entertained, 0).expect("Error in a reply `TmgEvent::Entertained`");
+        if stage != self.state.stage {
+            self.state.stage = stage;
+            self.notify_owner(TmgReply::Evolved(stage));
+        }
+    }
+
+    /// Refills the needs of a dead tamagotchi, which starts over as a baby born now.
+    fn revive(&mut self) -> Result<TmgReply, TmgError> {
+        if msg::source() != self.state.owner {
+            return Err(TmgError::NotOwner);
+        }
+        if self.state.stage != LifeStage::Dead {
+            return Err(TmgError::NotDead);
+        }
+        if msg::value() < REVIVE_COST {
+            return Err(TmgError::InsufficientValue);
+        }
+        self.update_streak();
+        let now = exec::block_timestamp();
+        self.state.fed = self.state.config.max_value;
+        self.state.fed_block = now;
+        self.state.entertained = self.state.config.max_value;
+        self.state.entertained_block = now;
+        self.state.rested = self.state.config.max_value;
+        self.state.rested_block = now;
+        self.state.date_of_birth = now;
+        self.state.stage = LifeStage::Baby;
+        if let Some(AutoCare { interval, .. }) = self.state.auto_care {
+            if !self.auto_care_scheduled {
+                // The owner is notified if there is no gas left to resume the auto-care.
+                self.schedule_check(interval).ok();
+            }
+        }
+        Ok(TmgReply::Revived)
+    }
+
+    fn reserve_gas(&mut self, amount: u64, duration: u32) -> Result<TmgReply, TmgError> {
+        if msg::source() != self.state.owner {
+            return Err(TmgError::NotOwner);
+        }
+        let id = exec::reserve_gas(amount, duration).map_err(|_| TmgError::Reservation)?;
+        self.reservations.push(Reservation {
+            id,
+            expires_at: exec::block_height().saturating_add(duration),
+        });
+        if let Some(AutoCare { interval, .. }) = self.state.auto_care {
+            if !self.auto_care_scheduled {
+                self.schedule_check(interval)?;
+            }
+        }
+        Ok(TmgReply::GasReserved)
+    }
+
+    fn set_auto_care(
+        &mut self,
+        interval: u32,
+        actions: Vec<CareAction>,
+    ) -> Result<TmgReply, TmgError> {
+        if msg::source() != self.state.owner {
+            return Err(TmgError::NotOwner);
+        }
+        if actions.is_empty() {
+            self.state.auto_care = None;
+            return Ok(TmgReply::AutoCareSet);
+        }
+        if interval == 0 {
+            return Err(TmgError::ZeroInterval);
+        }
+        let previous = self.state.auto_care.replace(AutoCare { interval, actions });
+        if !self.auto_care_scheduled {
+            if let Err(error) = self.schedule_check(interval) {
+                self.state.auto_care = previous;
+                return Err(error);
+            }
+        }
+        Ok(TmgReply::AutoCareSet)
     }
 
-    fn sleep(&mut self) {
-        assert!(!self.tmg_is_dead(), "Tamagotchi has died");
-        self.rested += FILL_PER_SLEEP - self.calculate_energy();
-        self.rested_block = exec::block_timestamp();
-        self.rested = if self.rested > MAX_VALUE {
-            MAX_VALUE
-        } else {
-            self.rested
+    /// Performs the auto-care and schedules the next check.
+    fn check_state(&mut self) -> Result<(), TmgError> {
+        self.auto_care_scheduled = false;
+        let Some(AutoCare { interval, actions }) = self.state.auto_care.clone() else {
+            return Ok(());
         };
-        msg::reply(TmgReply::Slept, 0).expect("Error in a reply `TmgEvent::Slept`");
+        if self.tmg_is_dead()? {
+            return Err(TmgError::Dead);
+        }
+        let current = self.current_state()?;
+        for action in actions {
+            let value = match action {
+                CareAction::Feed => current.fed,
+                CareAction::Play => current.entertained,
+                CareAction::Sleep => current.rested,
+            };
+            if value < AUTO_CARE_THRESHOLD {
+                self.fill(action, exec::program_id())?;
+            }
+        }
+        self.schedule_check(interval)
+    }
+
+    /// Sends a delayed `CheckState` to the program itself from the oldest
+    /// valid reservation, notifying the owner if reservations run low.
+    fn schedule_check(&mut self, interval: u32) -> Result<(), TmgError> {
+        let current_block = exec::block_height();
+        self.reservations
+            .retain(|reservation| reservation.expires_at > current_block);
+        if self.reservations.is_empty() {
+            self.notify_owner(TmgReply::ReservationLow(0));
+            return Err(TmgError::NoGasReserved);
+        }
+        let reservation = self.reservations.remove(0);
+        msg::send_delayed_from_reservation(
+            reservation.id,
+            exec::program_id(),
+            TmgAction::CheckState,
+            0,
+            interval,
+        )
+        .map_err(|_| TmgError::Reservation)?;
+        self.auto_care_scheduled = true;
+        if self.reservations.len() <= LOW_RESERVATIONS {
+            self.notify_owner(TmgReply::ReservationLow(self.reservations.len() as u32));
+        }
+        Ok(())
+    }
+
+    fn notify_owner(&self, reply: TmgReply) {
+        msg::send(self.state.owner, reply, 0)
+            .expect("Error in sending a notification to the owner");
+    }
+
+    fn tmg_info(&self) -> TmgReply {
+        TmgReply::TmgInfo {
+            owner: self.state.owner,
+            name: self.state.name.clone(),
+            date_of_birth: self.state.date_of_birth,
+        }
+    }
+
+    fn transfer(&mut self, new_owner: ActorId) -> Result<TmgReply, TmgError> {
+        if msg::source() != self.state.owner {
+            return Err(TmgError::NotOwner);
+        }
+        self.state.owner = new_owner;
+        self.state.allowed_account = None;
+        if let Some(factory_id) = self.state.factory_id {
+            msg::send(factory_id, FactoryAction::Transferred(new_owner), 0)
+                .expect("Error in notifying the factory about the transfer");
+        }
+        Ok(TmgReply::Transferred(new_owner))
+    }
+
+    fn approve(&mut self, account: ActorId) -> Result<TmgReply, TmgError> {
+        if msg::source() != self.state.owner {
+            return Err(TmgError::NotOwner);
+        }
+        self.state.allowed_account = Some(account);
+        Ok(TmgReply::Approved(account))
+    }
+
+    fn revoke_approval(&mut self) -> Result<TmgReply, TmgError> {
+        if msg::source() != self.state.owner {
+            return Err(TmgError::NotOwner);
+        }
+        self.state.allowed_account = None;
+        Ok(TmgReply::ApprovalRevoked)
+    }
+
+    /// Replaces the config, settling the needs under the previous one first
+    /// so that the new rates don't apply to the time already passed.
+    fn update_config(&mut self, config: TmgConfig) -> Result<TmgReply, TmgError> {
+        if msg::source() != self.state.owner {
+            return Err(TmgError::NotOwner);
+        }
+        config.validate()?;
+        let TmgCurrentState {
+            fed,
+            entertained,
+            rested,
+            ..
+        } = self.current_state()?;
+        self.update_streak();
+        let now = exec::block_timestamp();
+        self.state.fed = fed.min(config.max_value);
+        self.state.fed_block = now;
+        self.state.entertained = entertained.min(config.max_value);
+        self.state.entertained_block = now;
+        self.state.rested = rested.min(config.max_value);
+        self.state.rested_block = now;
+        self.state.config = config;
+        Ok(TmgReply::ConfigUpdated)
+    }
+
+    fn set_ft_contract(&mut self, ft_contract_id: ActorId) -> Result<TmgReply, TmgError> {
+        if msg::source() != self.state.owner {
+            return Err(TmgError::NotOwner);
+        }
+        self.state.ft_contract_id = Some(ft_contract_id);
+        Ok(TmgReply::FTokenContractSet)
     }
 
-    fn calculate_hunger(&self) -> u64 {
-        HUNGER_PER_BLOCK * ((exec::block_timestamp() - self.fed_block) / 1_000)
+    /// Allows `account` to spend `amount` tokens of the tamagotchi.
+    async fn approve_tokens(&self, account: ActorId, amount: u128) -> Result<TmgReply, TmgError> {
+        if msg::source() != self.state.owner {
+            return Err(TmgError::NotOwner);
+        }
+        let ft_contract_id = self
+            .state
+            .ft_contract_id
+            .ok_or(TmgError::FTokenContractNotSet)?;
+        msg::send_for_reply_as::<_, Result<FTEvent, FTError>>(
+            ft_contract_id,
+            FTAction::Approve {
+                to: account,
+                amount,
+            },
+            0,
+            0,
+        )
+        .map_err(|_| TmgError::TokensApproval)?
+        .await
+        .map_err(|_| TmgError::TokensApproval)?
+        .map_err(|_| TmgError::TokensApproval)?;
+        Ok(TmgReply::TokensApproved { account, amount })
+    }
+
+    /// Buys the attribute in the store and records it once the store confirms the sale.
+    async fn buy_attribute(
+        &mut self,
+        store_id: ActorId,
+        attribute_id: AttributeId,
+    ) -> Result<TmgReply, TmgError> {
+        if msg::source() != self.state.owner {
+            return Err(TmgError::NotOwner);
+        }
+        msg::send_for_reply_as::<_, Result<StoreEvent, StoreError>>(
+            store_id,
+            StoreAction::BuyAttribute { attribute_id },
+            0,
+            0,
+        )
+        .map_err(|_| TmgError::Purchase)?
+        .await
+        .map_err(|_| TmgError::Purchase)?
+        .map_err(|_| TmgError::Purchase)?;
+        if !self.state.attributes.contains(&attribute_id) {
+            self.state.attributes.push(attribute_id);
+        }
+        Ok(TmgReply::AttributePurchased(attribute_id))
     }
 
-    fn calculate_boredom(&self) -> u64 {
-        BOREDOM_PER_BLOCK * ((exec::block_timestamp() - self.entertained_block) / 1000)
+    fn set_factory(&mut self, factory_id: ActorId) -> Result<TmgReply, TmgError> {
+        if msg::source() != self.state.owner {
+            return Err(TmgError::NotOwner);
+        }
+        if self.state.factory_id.is_some() {
+            return Err(TmgError::FactoryAlreadySet);
+        }
+        self.state.factory_id = Some(factory_id);
+        Ok(TmgReply::FactorySet)
     }
 
-    fn calculate_energy(&self) -> u64 {
-        ENERGY_PER_BLOCK * ((exec::block_timestamp() - self.rested_block) / 1000)
+    fn approve_breeding(&mut self, partner: ActorId) -> Result<TmgReply, TmgError> {
+        if msg::source() != self.state.owner {
+            return Err(TmgError::NotOwner);
+        }
+        self.state.breeding_approval = Some(partner);
+        Ok(TmgReply::BreedingApproved(partner))
+    }
+
+    /// Checks that the tamagotchi is an adult that hasn't bred recently.
+    fn check_breeding(&self) -> Result<(), TmgError> {
+        if self.current_state()?.stage != LifeStage::Adult {
+            return Err(TmgError::NotAdult);
+        }
+        if self.state.last_bred.is_some_and(|last_bred| {
+            exec::block_timestamp().saturating_sub(last_bred) < BREEDING_COOLDOWN
+        }) {
+            return Err(TmgError::BreedingCooldown);
+        }
+        Ok(())
+    }
+
+    /// Gets the consent of `partner` and has the factory create the offspring for the owner.
+    /// The partner's consent and cooldown are given back if the factory fails.
+    ///
+    /// The cooldown starts before the awaits, so a concurrent `Breed` fails with
+    /// [`TmgError::BreedingCooldown`]. It's restored if the breeding fails.
+    async fn breed(&mut self, partner: ActorId, name: String) -> Result<TmgReply, TmgError> {
+        if msg::source() != self.state.owner {
+            return Err(TmgError::NotOwner);
+        }
+        let factory_id = self.state.factory_id.ok_or(TmgError::FactoryNotSet)?;
+        if partner == exec::program_id() {
+            return Err(TmgError::BreedingRefused);
+        }
+        self.check_breeding()?;
+
+        let last_bred = self.state.last_bred.replace(exec::block_timestamp());
+        // The cooldown of a consent given earlier can't be restored anymore.
+        self.confirmed_breeding = None;
+        let result = self.request_offspring(factory_id, partner, name).await;
+        if result.is_err() {
+            self.state.last_bred = last_bred;
+        }
+        result
+    }
+
+    /// Gets the consent of `partner` and has `factory_id` create the offspring.
+    async fn request_offspring(
+        &self,
+        factory_id: ActorId,
+        partner: ActorId,
+        name: String,
+    ) -> Result<TmgReply, TmgError> {
+        let reply = msg::send_for_reply_as::<_, Result<TmgReply, TmgError>>(
+            partner,
+            TmgAction::ConfirmBreeding,
+            0,
+            0,
+        )
+        .map_err(|_| TmgError::BreedingRefused)?
+        .await
+        .map_err(|_| TmgError::BreedingRefused)?
+        .map_err(|_| TmgError::BreedingRefused)?;
+        let TmgReply::BreedingConfirmed { traits, generation } = reply else {
+            return Err(TmgError::BreedingRefused);
+        };
+
+        let offspring = Offspring {
+            parents: (exec::program_id(), partner),
+            traits: self.state.traits.inherit(&traits),
+            generation: self
+                .state
+                .genealogy
+                .generation
+                .max(generation)
+                .saturating_add(1),
+        };
+        let Ok(FactoryEvent::OffspringCreated { tamagotchi_id, .. }) =
+            self.create_offspring(factory_id, name, offspring).await
+        else {
+            msg::send(partner, TmgAction::CancelBreeding, 0)
+                .expect("Error in giving the breeding consent back");
+            return Err(TmgError::Breeding);
+        };
+        Ok(TmgReply::Bred(tamagotchi_id))
     }
 
-    fn tmg_info(&self) {
-        msg::reply(
-            TmgReply::TmgInfo {
-                owner: self.owner,
-                name: self.name.clone(),
-                date_of_birth: self.date_of_birth,
+    async fn create_offspring(
+        &self,
+        factory_id: ActorId,
+        name: String,
+        offspring: Offspring,
+    ) -> Result<FactoryEvent, TmgError> {
+        msg::send_for_reply_as::<_, Result<FactoryEvent, FactoryError>>(
+            factory_id,
+            FactoryAction::CreateOffspring {
+                name,
+                owner: self.state.owner,
+                offspring,
             },
             0,
+            0,
         )
-        .expect("Error in a reply `TmgEvent::TmgInfo");
+        .map_err(|_| TmgError::Breeding)?
+        .await
+        .map_err(|_| TmgError::Breeding)?
+        .map_err(|_| TmgError::Breeding)
+    }
+
+    /// Gives the consent to the approved partner, which may use it once.
+    fn confirm_breeding(&mut self) -> Result<TmgReply, TmgError> {
+        if self.state.breeding_approval != Some(msg::source()) {
+            return Err(TmgError::BreedingRefused);
+        }
+        self.check_breeding()?;
+        self.state.breeding_approval = None;
+        let last_bred = self.state.last_bred.replace(exec::block_timestamp());
+        self.confirmed_breeding = Some((msg::source(), last_bred));
+        Ok(TmgReply::BreedingConfirmed {
+            traits: self.state.traits,
+            generation: self.state.genealogy.generation,
+        })
+    }
+
+    /// Gives back the consent spent by the partner whose factory failed,
+    /// unless the owner has approved someone else since.
+    fn cancel_breeding(&mut self) -> Result<TmgReply, TmgError> {
+        let Some((partner, last_bred)) = self.confirmed_breeding else {
+            return Err(TmgError::BreedingRefused);
+        };
+        if partner != msg::source() {
+            return Err(TmgError::BreedingRefused);
+        }
+        self.confirmed_breeding = None;
+        self.state.breeding_approval.get_or_insert(partner);
+        self.state.last_bred = last_bred;
+        Ok(TmgReply::BreedingCancelled)
+    }
+
+    fn mood(&self) -> Result<Mood, TmgError> {
+        let TmgCurrentState {
+            fed,
+            entertained,
+            rested,
+            ..
+        } = self.current_state()?;
+        Ok(self.state.config.mood(fed, entertained, rested))
+    }
+
+    fn stats(&self) -> Result<TmgReply, TmgError> {
+        let TmgCurrentState {
+            fed,
+            entertained,
+            rested,
+            ..
+        } = self.current_state()?;
+        Ok(TmgReply::Stats {
+            fed,
+            entertained,
+            rested,
+            attributes: self.state.attributes.clone(),
+        })
     }
 
-    fn tmg_is_dead(&self) -> bool {
-        let fed = self.fed.saturating_sub(self.calculate_hunger());
-        let entertained = self.entertained.saturating_sub(self.calculate_boredom());
-        let rested = self.rested.saturating_sub(self.calculate_energy());
-        fed == 0 && entertained == 0 && rested == 0
+    fn is_owner_or_approved(&self, account: &ActorId) -> bool {
+        self.state.owner == *account || self.state.allowed_account == Some(*account)
+    }
+
+    fn tmg_is_dead(&self) -> Result<bool, TmgError> {
+        Ok(self.current_state()?.stage == LifeStage::Dead)
     }
 }
 
-#[no_mangle]
-extern fn handle() {
+#[gstd::async_main]
+async fn main() {
     let action: TmgAction = msg::load().expect("Unable to decode `TmgAction`");
-    let tmg = unsafe { TAMAGOTCHI.get_or_insert(Default::default()) };
-    match action {
-        TmgAction::Name => {
-            msg::reply(TmgReply::Name(tmg.name.clone()), 0)
-                .expect("Error in a reply `TmgEvent::Name`");
-        }
-        TmgAction::Age => {
-            let age = exec::block_timestamp() - tmg.date_of_birth;
-            msg::reply(TmgReply::Age(age), 0).expect("Error in a reply `TmgEvent::Age`");
-        }
-        TmgAction::Feed => tmg.feed(),
-        TmgAction::Play => tmg.play(),
-        TmgAction::Sleep => tmg.sleep(),
-        TmgAction::TmgInfo => tmg.tmg_info(),
+    if let Some(tmg) = unsafe { TAMAGOTCHI.as_mut() } {
+        tmg.update_stage();
     }
+    let result = match unsafe { TAMAGOTCHI.as_mut() } {
+        Some(tmg) => match action {
+            TmgAction::CheckState if msg::source() == exec::program_id() => {
+                // There is no one to reply to, a failed check stops the auto-care.
+                tmg.check_state().ok();
+                return;
+            }
+            TmgAction::CheckState => Err(TmgError::NotOwner),
+            TmgAction::Name => Ok(TmgReply::Name(tmg.state.name.clone())),
+            TmgAction::Age => Ok(TmgReply::Age(
+                exec::block_timestamp() - tmg.state.date_of_birth,
+            )),
+            TmgAction::Feed => tmg.care(CareAction::Feed),
+            TmgAction::Play => tmg.care(CareAction::Play),
+            TmgAction::Sleep => tmg.care(CareAction::Sleep),
+            TmgAction::TmgInfo => Ok(tmg.tmg_info()),
+            TmgAction::Transfer(new_owner) => tmg.transfer(new_owner),
+            TmgAction::Approve(account) => tmg.approve(account),
+            TmgAction::RevokeApproval => tmg.revoke_approval(),
+            TmgAction::ReserveGas { amount, duration } => tmg.reserve_gas(amount, duration),
+            TmgAction::SetAutoCare { interval, actions } => tmg.set_auto_care(interval, actions),
+            TmgAction::SetFTokenContract(ft_contract_id) => tmg.set_ft_contract(ft_contract_id),
+            TmgAction::ApproveTokens { account, amount } => {
+                tmg.approve_tokens(account, amount).await
+            }
+            TmgAction::BuyAttribute {
+                store_id,
+                attribute_id,
+            } => tmg.buy_attribute(store_id, attribute_id).await,
+            TmgAction::Stats => tmg.stats(),
+            TmgAction::Revive => tmg.revive(),
+            TmgAction::UpdateConfig(config) => tmg.update_config(config),
+            TmgAction::Mood => tmg.mood().map(TmgReply::Mood),
+            TmgAction::SetFactory(factory_id) => tmg.set_factory(factory_id),
+            TmgAction::ApproveBreeding(partner) => tmg.approve_breeding(partner),
+            TmgAction::Breed { partner, name } => tmg.breed(partner, name).await,
+            TmgAction::ConfirmBreeding => tmg.confirm_breeding(),
+            TmgAction::CancelBreeding => tmg.cancel_breeding(),
+        },
+        None => Err(TmgError::NotInitialized),
+    };
+    // Only a successful revival keeps the attached value, the rest is returned.
+    let refund = match result {
+        Ok(TmgReply::Revived) => msg::value() - REVIVE_COST,
+        _ => msg::value(),
+    };
+    msg::reply(result, refund)
+        .expect("Failed to encode or reply with `Result<TmgReply, TmgError>`");
 }
 
 #[no_mangle]
 extern fn init() {
-    let TmgInit { name } = msg::load().expect("Failed to decode Tamagotchi name");
+    let TmgInit {
+        name,
+        config,
+        owner,
+        offspring,
+    } = msg::load().expect("Failed to decode Tamagotchi name");
+    let config = config.unwrap_or_default();
+    config.validate().expect("Invalid `TmgConfig`");
+    // Only a factory vouches for the genealogy, as the deployer it's recorded in `factory_id`.
+    assert!(
+        offspring.is_none() || owner.is_some(),
+        "Only a factory creating the tamagotchi for its owner can set `offspring`"
+    );
     let current_block = exec::block_timestamp();
+    let factory_id = owner.map(|_| msg::source());
+    let owner = owner.unwrap_or_else(msg::source);
+    let (traits, genealogy) = match offspring {
+        Some(Offspring {
+            parents,
+            traits,
+            generation,
+        }) => (
+            traits,
+            Genealogy {
+                parents: Some(parents),
+                generation,
+            },
+        ),
+        None => (Traits::new(&owner, &name), Genealogy::default()),
+    };
 
-    let tmg = Tamagotchi {
+    let state = tamagotchi_io::Tamagotchi {
         name,
         date_of_birth: current_block,
-        owner: msg::source(),
-        fed: MAX_VALUE,
+        owner,
+        fed: config.max_value,
         fed_block: current_block,
-        entertained: MAX_VALUE,
+        entertained: config.max_value,
         entertained_block: current_block,
-        rested: MAX_VALUE,
+        rested: config.max_value,
         rested_block: current_block,
+        config,
+        care_stats: CareStats {
+            streak_start: current_block,
+            ..Default::default()
+        },
+        traits,
+        factory_id,
+        genealogy,
+        ..Default::default()
     };
     unsafe {
-        TAMAGOTCHI = Some(tmg);
+        TAMAGOTCHI = Some(Tamagotchi {
+            state,
+            ..Default::default()
+        });
     }
 }
 
 #[no_mangle]
 extern fn state() {
     let tmg = unsafe { TAMAGOTCHI.take().expect("Unexpected error in taking state") };
-    msg::reply(tmg, 0).expect("Failed to share state");
+    let query: TmgStateQuery = msg::load().expect("Unable to decode `TmgStateQuery`");
+    let reply = match query {
+        TmgStateQuery::Full => TmgStateReply::Full(tmg.into()),
+        TmgStateQuery::CurrentState => TmgStateReply::CurrentState(
+            tmg.current_state()
+                .expect("Unable to compute the current state"),
+        ),
+        TmgStateQuery::Owner => TmgStateReply::Owner {
+            owner: tmg.state.owner,
+            allowed_account: tmg.state.allowed_account,
+        },
+        TmgStateQuery::Stage => TmgStateReply::Stage(
+            tmg.current_state()
+                .expect("Unable to compute the current state")
+                .stage,
+        ),
+        TmgStateQuery::Mood => {
+            TmgStateReply::Mood(tmg.mood().expect("Unable to compute the current state"))
+        }
+    };
+    msg::reply(reply, 0).expect("Failed to share state");
 }
 
-
-/*
-fn main() {
-    //gear_wasm_builder::build_with_metadata::<ProgramMetadata>();
-    WasmBuilder::with_meta(ProgramMetadata::repr())
-        .exclude_features(["binary-vendor"])
-        .build();
+impl From<Tamagotchi> for tamagotchi_io::Tamagotchi {
+    fn from(value: Tamagotchi) -> Self {
+        Self {
+            reservations: value.reservations.len() as u32,
+            ..value.state
+        }
+    }
 }
-
-*/
\ No newline at end of file
@@ -3,28 +3,29 @@
 use gstd::{exec, prelude::*};
 use tamagotchi_io::*;
 
-pub const HUNGER_PER_BLOCK: u64 = 1;
-pub const BOREDOM_PER_BLOCK: u64 = 2;
-pub const ENERGY_PER_BLOCK: u64 = 2;
+/// The metafunctions read the state with [`TmgStateQuery::Full`].
+fn full(state: TmgStateReply) -> Tamagotchi {
+    let TmgStateReply::Full(state) = state else {
+        panic!("The state must be read with `TmgStateQuery::Full`");
+    };
+    state
+}
 
 #[gmeta::metawasm]
 pub mod metafns {
-    pub type State = Tamagotchi;
+    pub type State = TmgStateReply;
 
     pub fn current_state(state: State) -> TmgCurrentState {
-        let fed = state.fed.saturating_sub(
-            HUNGER_PER_BLOCK * ((exec::block_timestamp() - state.fed_block) / 1_000),
-        );
-        let entertained = state.entertained.saturating_sub(
-            BOREDOM_PER_BLOCK * ((exec::block_timestamp() - state.entertained_block) / 1_000),
-        );
-        let rested = state.rested.saturating_sub(
-            ENERGY_PER_BLOCK * ((exec::block_timestamp() - state.rested_block) / 1_000),
-        );
-        TmgCurrentState {
-            fed,
-            entertained,
-            rested,
-        }
+        super::full(state)
+            .current_state(exec::block_timestamp())
+            .expect("Unable to compute the current state")
+    }
+
+    pub fn care_history(state: State) -> Vec<CareRecord> {
+        super::full(state).history
+    }
+
+    pub fn care_stats(state: State) -> CareSummary {
+        super::full(state).care_summary(exec::block_timestamp())
     }
 }
@@ -0,0 +1,112 @@
+use gstd::Encode;
+use gtest::{Program, System};
+use tamagotchi_io::*;
+
+mod utils;
+use utils::{full_state, init_tamagotchi};
+
+const OWNER: u64 = 3;
+const STRANGER: u64 = 5;
+const GAS_AMOUNT: u64 = 5_000_000_000;
+const DURATION: u32 = 10_000;
+
+fn reserve_gas(tmg: &Program<'_>) {
+    let res = tmg.send(
+        OWNER,
+        TmgAction::ReserveGas {
+            amount: GAS_AMOUNT,
+            duration: DURATION,
+        },
+    );
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::GasReserved).encode()
+    )));
+}
+
+#[test]
+fn auto_feed() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = init_tamagotchi(&sys, OWNER);
+
+    reserve_gas(&tmg);
+    reserve_gas(&tmg);
+
+    sys.spend_blocks(((MAX_VALUE - AUTO_CARE_THRESHOLD) / HUNGER_PER_BLOCK + 1) as u32);
+
+    let res = tmg.send(
+        OWNER,
+        TmgAction::SetAutoCare {
+            interval: 1,
+            actions: vec![CareAction::Feed],
+        },
+    );
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::AutoCareSet).encode()
+    )));
+    assert!(res.contains(&(OWNER, TmgReply::ReservationLow(1).encode())));
+
+    let results = sys.spend_blocks(2);
+    assert!(results
+        .iter()
+        .any(|res| res.contains(&(OWNER, TmgReply::ReservationLow(0).encode()))));
+
+    let state = full_state(&tmg);
+    assert!(state.fed > AUTO_CARE_THRESHOLD);
+    assert_eq!(state.reservations, 0);
+}
+
+#[test]
+fn failures() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = init_tamagotchi(&sys, OWNER);
+
+    let res = tmg.send(
+        OWNER,
+        TmgAction::SetAutoCare {
+            interval: 10,
+            actions: vec![CareAction::Play],
+        },
+    );
+    assert!(res.contains(&(
+        OWNER,
+        Err::<TmgReply, TmgError>(TmgError::NoGasReserved).encode()
+    )));
+    assert!(res.contains(&(OWNER, TmgReply::ReservationLow(0).encode())));
+
+    let res = tmg.send(
+        OWNER,
+        TmgAction::SetAutoCare {
+            interval: 0,
+            actions: vec![CareAction::Play],
+        },
+    );
+    assert!(res.contains(&(
+        OWNER,
+        Err::<TmgReply, TmgError>(TmgError::ZeroInterval).encode()
+    )));
+
+    let res = tmg.send(
+        STRANGER,
+        TmgAction::ReserveGas {
+            amount: GAS_AMOUNT,
+            duration: DURATION,
+        },
+    );
+    assert!(res.contains(&(
+        STRANGER,
+        Err::<TmgReply, TmgError>(TmgError::NotOwner).encode()
+    )));
+
+    let res = tmg.send(STRANGER, TmgAction::CheckState);
+    assert!(res.contains(&(
+        STRANGER,
+        Err::<TmgReply, TmgError>(TmgError::NotOwner).encode()
+    )));
+
+    let state = full_state(&tmg);
+    assert_eq!(state.auto_care, None);
+}
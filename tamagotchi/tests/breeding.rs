@@ -0,0 +1,267 @@
+use gstd::Encode;
+use gtest::{Program, System};
+use tamagotchi_factory_io::*;
+use tamagotchi_io::*;
+
+mod utils;
+use utils::{
+    full_state, init_factory, init_tamagotchi, program_id, tamagotchis, tmg_init, TAMAGOTCHI_WASM,
+};
+
+const OWNER: u64 = 3;
+const PARTNER_OWNER: u64 = 4;
+
+fn create_tamagotchi<'a>(sys: &'a System, factory: &Program<'a>, owner: u64) -> Program<'a> {
+    let res = factory.send(
+        owner,
+        FactoryAction::CreateTamagotchi {
+            name: String::from("Tama"),
+        },
+    );
+    assert!(!res.main_failed());
+
+    let id: [u8; 32] = tamagotchis(factory, owner)[0].into();
+    sys.get_program(id)
+}
+
+#[test]
+fn breed() {
+    let sys = System::new();
+    sys.init_logger();
+    let factory = init_factory(&sys, OWNER);
+    let first = create_tamagotchi(&sys, &factory, OWNER);
+    let second = create_tamagotchi(&sys, &factory, PARTNER_OWNER);
+
+    // The tamagotchis made by the factory know it from their init.
+    assert_eq!(full_state(&first).factory_id, Some(program_id(&factory)));
+
+    sys.spend_blocks((ADULT_AGE / 1_000) as u32);
+
+    let res = second.send(
+        PARTNER_OWNER,
+        TmgAction::ApproveBreeding(program_id(&first)),
+    );
+    assert!(res.contains(&(
+        PARTNER_OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::BreedingApproved(program_id(&first))).encode()
+    )));
+
+    let res = first.send(
+        OWNER,
+        TmgAction::Breed {
+            partner: program_id(&second),
+            name: String::from("Tama Jr."),
+        },
+    );
+    let offspring_id = tamagotchis(&factory, OWNER)[1];
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::Bred(offspring_id)).encode()
+    )));
+
+    let first_state = full_state(&first);
+    let second_state = full_state(&second);
+    let id: [u8; 32] = offspring_id.into();
+    let offspring = full_state(&sys.get_program(id));
+    assert_eq!(offspring.name, "Tama Jr.");
+    assert_eq!(offspring.owner, OWNER.into());
+    assert_eq!(
+        offspring.traits,
+        first_state.traits.inherit(&second_state.traits)
+    );
+    assert_eq!(
+        offspring.genealogy,
+        Genealogy {
+            parents: Some((program_id(&first), program_id(&second))),
+            generation: 1,
+        }
+    );
+    assert_eq!(offspring.factory_id, Some(program_id(&factory)));
+    assert_eq!(first_state.last_bred, second_state.last_bred);
+    assert_eq!(second_state.breeding_approval, None);
+}
+
+#[test]
+fn forged_offspring() {
+    let sys = System::new();
+    sys.init_logger();
+    let forged = Some(Offspring {
+        parents: (1.into(), 2.into()),
+        traits: Traits::default(),
+        generation: 100,
+    });
+
+    // Only a factory creating the tamagotchi for someone else may set the genealogy.
+    let tmg = Program::from_file(&sys, TAMAGOTCHI_WASM);
+    let res = tmg.send(
+        OWNER,
+        TmgInit {
+            offspring: forged.clone(),
+            ..tmg_init(None)
+        },
+    );
+    assert!(res.main_failed());
+
+    // The deployer claiming to be a factory is the only one vouching for the genealogy,
+    // the real factory can't be set afterwards.
+    let factory = init_factory(&sys, OWNER);
+    let tmg = Program::from_file(&sys, TAMAGOTCHI_WASM);
+    let res = tmg.send(
+        OWNER,
+        TmgInit {
+            owner: Some(OWNER.into()),
+            offspring: forged,
+            ..tmg_init(None)
+        },
+    );
+    assert!(!res.main_failed());
+
+    let res = tmg.send(OWNER, TmgAction::SetFactory(program_id(&factory)));
+    assert!(res.contains(&(
+        OWNER,
+        Err::<TmgReply, TmgError>(TmgError::FactoryAlreadySet).encode()
+    )));
+    assert_eq!(full_state(&tmg).factory_id, Some(OWNER.into()));
+}
+
+#[test]
+fn failures() {
+    let sys = System::new();
+    sys.init_logger();
+    let factory = init_factory(&sys, OWNER);
+    let first = create_tamagotchi(&sys, &factory, OWNER);
+    let second = create_tamagotchi(&sys, &factory, PARTNER_OWNER);
+    let breed = || {
+        first.send(
+            OWNER,
+            TmgAction::Breed {
+                partner: program_id(&second),
+                name: String::from("Tama Jr."),
+            },
+        )
+    };
+
+    let res = breed();
+    assert!(res.contains(&(
+        OWNER,
+        Err::<TmgReply, TmgError>(TmgError::NotAdult).encode()
+    )));
+
+    sys.spend_blocks((ADULT_AGE / 1_000) as u32);
+
+    // The partner's owner hasn't approved the breeding yet.
+    let res = breed();
+    assert!(res.contains(&(
+        OWNER,
+        Err::<TmgReply, TmgError>(TmgError::BreedingRefused).encode()
+    )));
+
+    let res = second.send(
+        PARTNER_OWNER,
+        TmgAction::ApproveBreeding(program_id(&first)),
+    );
+    assert!(!res.main_failed());
+    let res = breed();
+    assert!(!res.main_failed());
+
+    let res = second.send(
+        PARTNER_OWNER,
+        TmgAction::ApproveBreeding(program_id(&first)),
+    );
+    assert!(!res.main_failed());
+    let res = breed();
+    assert!(res.contains(&(
+        OWNER,
+        Err::<TmgReply, TmgError>(TmgError::BreedingCooldown).encode()
+    )));
+
+    // Only the first parent may ask the factory for the offspring.
+    let res = factory.send(
+        OWNER,
+        FactoryAction::CreateOffspring {
+            name: String::from("Tama Jr."),
+            owner: OWNER.into(),
+            offspring: Offspring {
+                parents: (program_id(&first), program_id(&second)),
+                traits: Traits::default(),
+                generation: 1,
+            },
+        },
+    );
+    assert!(res.contains(&(
+        OWNER,
+        Err::<FactoryEvent, FactoryError>(FactoryError::UnknownParents).encode()
+    )));
+}
+
+#[test]
+fn factory_not_set() {
+    let sys = System::new();
+    sys.init_logger();
+    let factory = init_factory(&sys, OWNER);
+    let partner = create_tamagotchi(&sys, &factory, PARTNER_OWNER);
+
+    let tmg = init_tamagotchi(&sys, OWNER);
+    assert_eq!(full_state(&tmg).factory_id, None);
+
+    let res = tmg.send(
+        OWNER,
+        TmgAction::Breed {
+            partner: program_id(&partner),
+            name: String::from("Tama Jr."),
+        },
+    );
+    assert!(res.contains(&(
+        OWNER,
+        Err::<TmgReply, TmgError>(TmgError::FactoryNotSet).encode()
+    )));
+
+    let res = tmg.send(OWNER, TmgAction::SetFactory(program_id(&factory)));
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::FactorySet).encode()
+    )));
+
+    let res = tmg.send(OWNER, TmgAction::SetFactory(OWNER.into()));
+    assert!(res.contains(&(
+        OWNER,
+        Err::<TmgReply, TmgError>(TmgError::FactoryAlreadySet).encode()
+    )));
+}
+
+#[test]
+fn factory_failed() {
+    let sys = System::new();
+    sys.init_logger();
+    let factory = init_factory(&sys, OWNER);
+    let partner = create_tamagotchi(&sys, &factory, PARTNER_OWNER);
+
+    // The factory doesn't know the tamagotchi, so it refuses to create the offspring.
+    let tmg = init_tamagotchi(&sys, OWNER);
+    let res = tmg.send(OWNER, TmgAction::SetFactory(program_id(&factory)));
+    assert!(!res.main_failed());
+
+    sys.spend_blocks((ADULT_AGE / 1_000) as u32);
+
+    let res = partner.send(PARTNER_OWNER, TmgAction::ApproveBreeding(program_id(&tmg)));
+    assert!(!res.main_failed());
+
+    let res = tmg.send(
+        OWNER,
+        TmgAction::Breed {
+            partner: program_id(&partner),
+            name: String::from("Tama Jr."),
+        },
+    );
+    assert!(res.contains(&(
+        OWNER,
+        Err::<TmgReply, TmgError>(TmgError::Breeding).encode()
+    )));
+
+    // Both keep their cooldowns and the partner's consent is given back.
+    let partner_state = full_state(&partner);
+    assert_eq!(partner_state.breeding_approval, Some(program_id(&tmg)));
+    assert_eq!(partner_state.last_bred, None);
+    assert_eq!(full_state(&tmg).last_bred, None);
+    assert_eq!(tamagotchis(&factory, OWNER), vec![]);
+}
@@ -0,0 +1,124 @@
+use gstd::{Decode, Encode};
+use gtest::{Program, System};
+use tamagotchi_io::*;
+
+mod utils;
+use utils::{full_state, init_tamagotchi, init_tamagotchi_with_config, tmg_init, TAMAGOTCHI_WASM};
+
+const OWNER: u64 = 3;
+const STRANGER: u64 = 4;
+
+#[test]
+fn init_config() {
+    let sys = System::new();
+    sys.init_logger();
+    let config = TmgConfig {
+        hunger_per_block: 10,
+        max_value: 5_000,
+        block_duration: 2_000,
+        ..Default::default()
+    };
+    let tmg = init_tamagotchi_with_config(&sys, OWNER, Some(config));
+
+    sys.spend_blocks(10);
+
+    let state = full_state(&tmg);
+    assert_eq!(state.config, config);
+    assert_eq!(state.fed, 5_000);
+
+    let decay = state.decay_config();
+
+    let res = tmg.send(OWNER, TmgAction::Stats);
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::Stats {
+            fed: 5_000 - decay.hunger_per_block * 5,
+            entertained: 5_000 - decay.boredom_per_block * 5,
+            rested: 5_000 - decay.energy_per_block * 5,
+            attributes: vec![],
+        })
+        .encode()
+    )));
+}
+
+#[test]
+fn invalid_init_config() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = Program::from_file(&sys, TAMAGOTCHI_WASM);
+
+    let res = tmg.send(
+        OWNER,
+        tmg_init(Some(TmgConfig {
+            block_duration: 0,
+            ..Default::default()
+        })),
+    );
+    assert!(res.main_failed());
+}
+
+#[test]
+fn update_config() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = init_tamagotchi(&sys, OWNER);
+    let config = TmgConfig {
+        hunger_per_block: 100,
+        max_value: 1_000,
+        fill_per_feed: 500,
+        fill_per_entertainment: 500,
+        fill_per_sleep: 500,
+        ..Default::default()
+    };
+
+    let res = tmg.send(STRANGER, TmgAction::UpdateConfig(config));
+    assert!(res.contains(&(
+        STRANGER,
+        Err::<TmgReply, TmgError>(TmgError::NotOwner).encode()
+    )));
+
+    let res = tmg.send(
+        OWNER,
+        TmgAction::UpdateConfig(TmgConfig {
+            fill_per_feed: 2_000,
+            ..config
+        }),
+    );
+    assert!(res.contains(&(
+        OWNER,
+        Err::<TmgReply, TmgError>(TmgError::InvalidConfig).encode()
+    )));
+
+    let res = tmg.send(OWNER, TmgAction::UpdateConfig(config));
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::ConfigUpdated).encode()
+    )));
+
+    // The needs are clamped to the new maximum and decay at the new rates from now on.
+    let state = full_state(&tmg);
+    assert_eq!(state.config, config);
+    assert_eq!(state.fed, 1_000);
+
+    let decay = state.decay_config();
+
+    sys.spend_blocks(10);
+
+    let res = tmg.send(OWNER, TmgAction::Stats);
+    let reply = res
+        .log()
+        .iter()
+        .find(|log| log.destination() == OWNER.into())
+        .map(|log| Result::<TmgReply, TmgError>::decode(&mut log.payload()))
+        .expect("Can't find the reply")
+        .expect("Can't decode the reply");
+    let Ok(TmgReply::Stats {
+        fed, entertained, ..
+    }) = reply
+    else {
+        panic!("Unexpected reply: {reply:?}");
+    };
+    let decayed = |decay_per_block: u64| 1_000u64.saturating_sub(decay_per_block * 10);
+    assert!(fed <= decayed(decay.hunger_per_block));
+    assert!(entertained > 0 && entertained <= decayed(decay.boredom_per_block));
+}
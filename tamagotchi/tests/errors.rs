@@ -0,0 +1,97 @@
+use gstd::Encode;
+use gtest::System;
+use tamagotchi_io::*;
+
+mod utils;
+use utils::{full_state, init_tamagotchi, mood};
+
+const OWNER: u64 = 3;
+
+#[test]
+fn cooldown() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = init_tamagotchi(&sys, OWNER);
+
+    // A newborn can be cared for right away, only a manual care starts the cooldown.
+    let res = tmg.send(OWNER, TmgAction::Feed);
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::Fed(Mood::Happy)).encode()
+    )));
+
+    let res = tmg.send(OWNER, TmgAction::Feed);
+    assert!(res.contains(&(
+        OWNER,
+        Err::<TmgReply, TmgError>(TmgError::Cooldown).encode()
+    )));
+
+    let res = tmg.send(OWNER, TmgAction::Sleep);
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::Slept(Mood::Happy)).encode()
+    )));
+
+    // Updating the config doesn't restart the cooldown.
+    sys.spend_blocks((CARE_COOLDOWN / 1_000) as u32);
+    let res = tmg.send(OWNER, TmgAction::UpdateConfig(TmgConfig::default()));
+    assert!(!res.main_failed());
+
+    let res = tmg.send(OWNER, TmgAction::Feed);
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::Fed(Mood::Happy)).encode()
+    )));
+}
+
+#[test]
+fn fill_after_long_idle() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = init_tamagotchi(&sys, OWNER);
+
+    let config = full_state(&tmg).decay_config();
+
+    // Boredom grows beyond the fill of a single play.
+    sys.spend_blocks((FILL_PER_ENTERTAINMENT / config.boredom_per_block + 1) as u32);
+    let before = full_state(&tmg);
+
+    let res = tmg.send(OWNER, TmgAction::Play);
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::Entertained(mood(&tmg))).encode()
+    )));
+
+    let after = full_state(&tmg);
+    let current = config
+        .current_need(
+            before.entertained,
+            before.entertained_block,
+            after.entertained_block,
+            config.boredom_per_block,
+        )
+        .expect("Timestamps must grow");
+
+    assert!(current < MAX_VALUE - FILL_PER_ENTERTAINMENT);
+    assert_eq!(after.entertained, current + FILL_PER_ENTERTAINMENT);
+}
+
+#[test]
+fn dead() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = init_tamagotchi(&sys, OWNER);
+
+    sys.spend_blocks((MAX_VALUE / HUNGER_PER_BLOCK) as u32);
+
+    for action in [TmgAction::Feed, TmgAction::Play, TmgAction::Sleep] {
+        let res = tmg.send(OWNER, action);
+        assert!(res.contains(&(OWNER, Err::<TmgReply, TmgError>(TmgError::Dead).encode())));
+    }
+
+    let res = tmg.send(OWNER, TmgAction::Name);
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::Name(String::from("Tama"))).encode()
+    )));
+}
@@ -0,0 +1,108 @@
+use gstd::Encode;
+use gtest::{Program, System};
+use tamagotchi_io::*;
+
+mod utils;
+use utils::{full_state, init_tamagotchi};
+
+const OWNER: u64 = 3;
+const APPROVED: u64 = 4;
+const META_WASM: &str = "../target/wasm32-unknown-unknown/debug/tamagotchi_state.meta.wasm";
+
+fn care_history(tmg: &Program<'_>) -> Vec<CareRecord> {
+    let wasm = std::fs::read(META_WASM).expect("Can't read metawasm");
+    tmg.read_state_using_wasm(TmgStateQuery::Full, "care_history", wasm, None::<()>)
+        .expect("Can't read state using metawasm")
+}
+
+fn care_stats(tmg: &Program<'_>) -> CareSummary {
+    let wasm = std::fs::read(META_WASM).expect("Can't read metawasm");
+    tmg.read_state_using_wasm(TmgStateQuery::Full, "care_stats", wasm, None::<()>)
+        .expect("Can't read state using metawasm")
+}
+
+#[test]
+fn records_cares() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = init_tamagotchi(&sys, OWNER);
+
+    let res = tmg.send(OWNER, TmgAction::Feed);
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::Fed(Mood::Happy)).encode()
+    )));
+
+    let res = tmg.send(OWNER, TmgAction::Approve(APPROVED.into()));
+    assert!(!res.main_failed());
+    let res = tmg.send(APPROVED, TmgAction::Play);
+    assert!(res.contains(&(
+        APPROVED,
+        Ok::<TmgReply, TmgError>(TmgReply::Entertained(Mood::Happy)).encode()
+    )));
+
+    let history = care_history(&tmg);
+    assert_eq!(
+        history
+            .iter()
+            .map(|record| (record.action, record.actor))
+            .collect::<Vec<_>>(),
+        vec![
+            (CareAction::Feed, OWNER.into()),
+            (CareAction::Play, APPROVED.into())
+        ]
+    );
+    assert!(history[0].timestamp < history[1].timestamp);
+
+    let stats = care_stats(&tmg);
+    assert_eq!(stats.total_feeds, 1);
+    assert_eq!(stats.total_plays, 1);
+    assert_eq!(stats.total_sleeps, 0);
+    assert!(stats.average_need > 0 && stats.average_need <= MAX_VALUE);
+    assert_eq!(stats.longest_streak, stats.current_streak);
+}
+
+#[test]
+fn history_is_bounded() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = init_tamagotchi(&sys, OWNER);
+
+    let rounds = HISTORY_LEN / 3 + 1;
+    for _ in 0..rounds {
+        for action in [TmgAction::Feed, TmgAction::Play, TmgAction::Sleep] {
+            let res = tmg.send(OWNER, action);
+            assert!(!res.main_failed());
+        }
+        sys.spend_blocks((CARE_COOLDOWN / 1_000) as u32);
+    }
+
+    let history = care_history(&tmg);
+    assert_eq!(history.len(), HISTORY_LEN);
+    assert_eq!(
+        history.last().map(|record| record.action),
+        Some(CareAction::Sleep)
+    );
+    assert_eq!(care_stats(&tmg).total_feeds, rounds as u64);
+}
+
+#[test]
+fn streak() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = init_tamagotchi(&sys, OWNER);
+
+    let state = full_state(&tmg);
+    let first_zero = state.first_zero();
+    sys.spend_blocks(((first_zero - sys.block_timestamp()) / 1_000) as u32);
+
+    let res = tmg.send(OWNER, TmgAction::Play);
+    assert!(!res.main_failed());
+
+    let stats = care_stats(&tmg);
+    assert_eq!(
+        stats.longest_streak,
+        first_zero - state.care_stats.streak_start
+    );
+    assert!(stats.current_streak < stats.longest_streak);
+}
@@ -0,0 +1,76 @@
+use gstd::Encode;
+use gtest::System;
+use tamagotchi_io::*;
+
+mod utils;
+use utils::{full_state, init_tamagotchi};
+
+const OWNER: u64 = 3;
+
+#[test]
+fn evolve() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = init_tamagotchi(&sys, OWNER);
+
+    sys.spend_blocks((HATCH_AGE / 1_000) as u32);
+
+    let res = tmg.send(OWNER, TmgAction::Name);
+    assert!(res.contains(&(OWNER, TmgReply::Evolved(LifeStage::Baby).encode())));
+
+    sys.spend_blocks(((ADULT_AGE - HATCH_AGE) / 1_000) as u32);
+
+    let res = tmg.send(OWNER, TmgAction::Name);
+    assert!(res.contains(&(OWNER, TmgReply::Evolved(LifeStage::Adult).encode())));
+
+    let state = full_state(&tmg);
+    assert_eq!(state.stage, LifeStage::Adult);
+}
+
+#[test]
+fn revive() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = init_tamagotchi(&sys, OWNER);
+    sys.mint_to(OWNER, REVIVE_COST * 2);
+
+    let res = tmg.send_with_value(OWNER, TmgAction::Revive, REVIVE_COST);
+    assert!(res.contains(&(OWNER, Err::<TmgReply, TmgError>(TmgError::NotDead).encode())));
+    sys.claim_value_from_mailbox(OWNER);
+
+    sys.spend_blocks((MAX_VALUE / HUNGER_PER_BLOCK) as u32);
+
+    let res = tmg.send_with_value(OWNER, TmgAction::Revive, REVIVE_COST - 1);
+    assert!(res.contains(&(OWNER, TmgReply::Evolved(LifeStage::Dead).encode())));
+    assert!(res.contains(&(
+        OWNER,
+        Err::<TmgReply, TmgError>(TmgError::InsufficientValue).encode()
+    )));
+    sys.claim_value_from_mailbox(OWNER);
+
+    let res = tmg.send_with_value(OWNER, TmgAction::Revive, REVIVE_COST);
+    assert!(res.contains(&(OWNER, Ok::<TmgReply, TmgError>(TmgReply::Revived).encode())));
+    sys.claim_value_from_mailbox(OWNER);
+    // Only the cost of the successful revival is kept.
+    assert_eq!(sys.balance_of(OWNER), REVIVE_COST);
+
+    let state = full_state(&tmg);
+    assert_eq!(state.stage, LifeStage::Baby);
+    assert_eq!(state.fed, MAX_VALUE);
+    assert_eq!(state.date_of_birth, state.fed_block);
+
+    // The revived tamagotchi can be fed right away.
+    let res = tmg.send(OWNER, TmgAction::Feed);
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::Fed(Mood::Happy)).encode()
+    )));
+
+    // The age starts over, so the revived baby doesn't grow up right away.
+    sys.spend_blocks((HATCH_AGE / BLOCK_DURATION) as u32);
+
+    let res = tmg.send(OWNER, TmgAction::Name);
+    assert!(!res.main_failed());
+    assert!(!res.contains(&(OWNER, TmgReply::Evolved(LifeStage::Adult).encode())));
+    assert_eq!(full_state(&tmg).stage, LifeStage::Baby);
+}
@@ -0,0 +1,102 @@
+use gstd::Encode;
+use gtest::System;
+use tamagotchi_io::*;
+
+mod utils;
+use utils::init_tamagotchi;
+
+const OWNER: u64 = 3;
+const APPROVED: u64 = 4;
+const STRANGER: u64 = 5;
+
+#[test]
+fn care_is_restricted() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = init_tamagotchi(&sys, OWNER);
+
+    let res = tmg.send(STRANGER, TmgAction::Feed);
+    assert!(res.contains(&(
+        STRANGER,
+        Err::<TmgReply, TmgError>(TmgError::NotOwner).encode()
+    )));
+
+    let res = tmg.send(OWNER, TmgAction::Approve(APPROVED.into()));
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::Approved(APPROVED.into())).encode()
+    )));
+
+    let res = tmg.send(APPROVED, TmgAction::Play);
+    assert!(res.contains(&(
+        APPROVED,
+        Ok::<TmgReply, TmgError>(TmgReply::Entertained(Mood::Happy)).encode()
+    )));
+
+    let res = tmg.send(OWNER, TmgAction::RevokeApproval);
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::ApprovalRevoked).encode()
+    )));
+
+    let res = tmg.send(APPROVED, TmgAction::Sleep);
+    assert!(res.contains(&(
+        APPROVED,
+        Err::<TmgReply, TmgError>(TmgError::NotOwner).encode()
+    )));
+}
+
+#[test]
+fn transfer() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = init_tamagotchi(&sys, OWNER);
+
+    let res = tmg.send(STRANGER, TmgAction::Transfer(STRANGER.into()));
+    assert!(res.contains(&(
+        STRANGER,
+        Err::<TmgReply, TmgError>(TmgError::NotOwner).encode()
+    )));
+
+    let res = tmg.send(STRANGER, TmgAction::Approve(STRANGER.into()));
+    assert!(res.contains(&(
+        STRANGER,
+        Err::<TmgReply, TmgError>(TmgError::NotOwner).encode()
+    )));
+
+    let res = tmg.send(STRANGER, TmgAction::RevokeApproval);
+    assert!(res.contains(&(
+        STRANGER,
+        Err::<TmgReply, TmgError>(TmgError::NotOwner).encode()
+    )));
+
+    let res = tmg.send(OWNER, TmgAction::Approve(APPROVED.into()));
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::Approved(APPROVED.into())).encode()
+    )));
+
+    let res = tmg.send(OWNER, TmgAction::Transfer(STRANGER.into()));
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::Transferred(STRANGER.into())).encode()
+    )));
+
+    let res = tmg.send(OWNER, TmgAction::Feed);
+    assert!(res.contains(&(
+        OWNER,
+        Err::<TmgReply, TmgError>(TmgError::NotOwner).encode()
+    )));
+
+    let res = tmg.send(APPROVED, TmgAction::Feed);
+    assert!(res.contains(&(
+        APPROVED,
+        Err::<TmgReply, TmgError>(TmgError::NotOwner).encode()
+    )));
+
+    let res = tmg.send(STRANGER, TmgAction::Feed);
+    assert!(res.contains(&(
+        STRANGER,
+        Ok::<TmgReply, TmgError>(TmgReply::Fed(Mood::Happy)).encode()
+    )));
+}
@@ -0,0 +1,142 @@
+use gstd::Encode;
+use gtest::System;
+use tamagotchi_io::*;
+
+mod utils;
+use utils::{full_state, init_tamagotchi};
+
+const OWNER: u64 = 3;
+const META_WASM: &str = "../target/wasm32-unknown-unknown/debug/tamagotchi_state.meta.wasm";
+
+#[test]
+fn read_state() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = init_tamagotchi(&sys, OWNER);
+
+    let state = full_state(&tmg);
+
+    assert_eq!(state.name, "Tama");
+    assert_eq!(state.owner, OWNER.into());
+    assert_eq!(state.fed, MAX_VALUE);
+    assert_eq!(state.entertained, MAX_VALUE);
+    assert_eq!(state.rested, MAX_VALUE);
+    assert_eq!(state.allowed_account, None);
+    assert_eq!(state.stage, LifeStage::Egg);
+}
+
+#[test]
+fn current_state() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = init_tamagotchi(&sys, OWNER);
+
+    sys.spend_blocks(100);
+
+    let state = full_state(&tmg);
+    let decay = state.decay_config();
+    let wasm = std::fs::read(META_WASM).expect("Can't read metawasm");
+    let current_state: TmgCurrentState = tmg
+        .read_state_using_wasm(TmgStateQuery::Full, "current_state", wasm, None::<()>)
+        .expect("Can't read state using metawasm");
+
+    let elapsed = |block: u64| (sys.block_timestamp() - block) / 1_000;
+
+    assert_eq!(
+        current_state,
+        TmgCurrentState {
+            fed: state.fed - decay.hunger_per_block * elapsed(state.fed_block),
+            entertained: state.entertained
+                - decay.boredom_per_block * elapsed(state.entertained_block),
+            rested: state.rested - decay.energy_per_block * elapsed(state.rested_block),
+            stage: LifeStage::Baby,
+        }
+    );
+}
+
+#[test]
+fn queries() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = init_tamagotchi(&sys, OWNER);
+
+    let query = |query| -> TmgStateReply { tmg.read_state(query).expect("Can't read state") };
+
+    assert_eq!(
+        query(TmgStateQuery::Owner),
+        TmgStateReply::Owner {
+            owner: OWNER.into(),
+            allowed_account: None,
+        }
+    );
+    assert_eq!(
+        query(TmgStateQuery::Stage),
+        TmgStateReply::Stage(LifeStage::Egg)
+    );
+    assert_eq!(query(TmgStateQuery::Mood), TmgStateReply::Mood(Mood::Happy));
+
+    // The fastest decaying need drops below a half.
+    let decay = full_state(&tmg).decay_config();
+    let fastest = decay
+        .hunger_per_block
+        .max(decay.boredom_per_block)
+        .max(decay.energy_per_block);
+    sys.spend_blocks((MAX_VALUE / 2 / fastest + 1) as u32);
+
+    assert_eq!(
+        query(TmgStateQuery::Stage),
+        TmgStateReply::Stage(LifeStage::Baby)
+    );
+
+    let TmgStateReply::CurrentState(TmgCurrentState {
+        fed,
+        entertained,
+        rested,
+        ..
+    }) = query(TmgStateQuery::CurrentState)
+    else {
+        panic!("Unexpected state reply");
+    };
+    assert!(fed.min(entertained).min(rested) < MAX_VALUE / 2);
+
+    let mood = decay.mood(fed, entertained, rested);
+    assert_ne!(mood, Mood::Happy);
+    assert_eq!(query(TmgStateQuery::Mood), TmgStateReply::Mood(mood));
+}
+
+#[test]
+fn traits() {
+    let sys = System::new();
+    sys.init_logger();
+    let tmg = init_tamagotchi(&sys, OWNER);
+
+    let state = full_state(&tmg);
+    assert_eq!(state.traits, Traits::new(&OWNER.into(), "Tama"));
+    for percent in [
+        state.traits.appetite,
+        state.traits.playfulness,
+        state.traits.sleepiness,
+    ] {
+        assert!(TRAIT_RANGE.contains(&percent));
+    }
+
+    let res = tmg.send(OWNER, TmgAction::Mood);
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::Mood(Mood::Happy)).encode()
+    )));
+
+    // The tamagotchi gets sick once the fastest decaying need is empty.
+    let decay = state.decay_config();
+    let fastest = decay
+        .hunger_per_block
+        .max(decay.boredom_per_block)
+        .max(decay.energy_per_block);
+    sys.spend_blocks(MAX_VALUE.div_ceil(fastest) as u32);
+
+    let res = tmg.send(OWNER, TmgAction::Mood);
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::Mood(Mood::Sick)).encode()
+    )));
+}
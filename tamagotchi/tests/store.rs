@@ -0,0 +1,165 @@
+use fungible_token_io::*;
+use gstd::Encode;
+use gtest::{Program, System};
+use tamagotchi_io::*;
+use tamagotchi_store_io::*;
+
+mod utils;
+use utils::{full_state, init_tamagotchi, program_id};
+
+const FT_WASM: &str = "../target/wasm32-unknown-unknown/debug/fungible_token.opt.wasm";
+const STORE_WASM: &str = "../target/wasm32-unknown-unknown/debug/tamagotchi_store.opt.wasm";
+
+const OWNER: u64 = 3;
+const ADMIN: u64 = 4;
+
+const ATTRIBUTE_ID: AttributeId = 1;
+const PRICE: u128 = 1_000;
+
+fn init_programs(sys: &System) -> (Program<'_>, Program<'_>, Program<'_>) {
+    let tmg = init_tamagotchi(sys, OWNER);
+
+    let ft = Program::from_file(sys, FT_WASM);
+    let res = ft.send(
+        ADMIN,
+        InitFToken {
+            name: String::from("Tama Coin"),
+            symbol: String::from("TMC"),
+            decimals: 0,
+        },
+    );
+    assert!(!res.main_failed());
+
+    let store = Program::from_file(sys, STORE_WASM);
+    let res = store.send(
+        ADMIN,
+        InitStore {
+            ft_contract: program_id(&ft),
+        },
+    );
+    assert!(!res.main_failed());
+
+    let res = store.send(
+        ADMIN,
+        StoreAction::CreateAttribute {
+            attribute_id: ATTRIBUTE_ID,
+            metadata: AttributeMetadata {
+                title: String::from("Hat"),
+                ..Default::default()
+            },
+            price: PRICE,
+        },
+    );
+    assert!(res.contains(&(
+        ADMIN,
+        Ok::<StoreEvent, StoreError>(StoreEvent::AttributeCreated(ATTRIBUTE_ID)).encode()
+    )));
+
+    (tmg, ft, store)
+}
+
+fn fund_tamagotchi(ft: &Program<'_>, tmg: &Program<'_>, amount: u128) {
+    let res = ft.send(ADMIN, FTAction::Mint(amount));
+    assert!(!res.main_failed());
+
+    let res = ft.send(
+        ADMIN,
+        FTAction::Transfer {
+            from: ADMIN.into(),
+            to: program_id(tmg),
+            amount,
+        },
+    );
+    assert!(!res.main_failed());
+}
+
+#[test]
+fn buy_attribute() {
+    let sys = System::new();
+    sys.init_logger();
+    let (tmg, ft, store) = init_programs(&sys);
+    fund_tamagotchi(&ft, &tmg, PRICE);
+
+    let res = tmg.send(OWNER, TmgAction::SetFTokenContract(program_id(&ft)));
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::FTokenContractSet).encode()
+    )));
+
+    let res = tmg.send(
+        OWNER,
+        TmgAction::ApproveTokens {
+            account: program_id(&store),
+            amount: PRICE,
+        },
+    );
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::TokensApproved {
+            account: program_id(&store),
+            amount: PRICE,
+        })
+        .encode()
+    )));
+
+    let res = tmg.send(
+        OWNER,
+        TmgAction::BuyAttribute {
+            store_id: program_id(&store),
+            attribute_id: ATTRIBUTE_ID,
+        },
+    );
+    assert!(res.contains(&(
+        OWNER,
+        Ok::<TmgReply, TmgError>(TmgReply::AttributePurchased(ATTRIBUTE_ID)).encode()
+    )));
+
+    let state = full_state(&tmg);
+    assert_eq!(state.attributes, vec![ATTRIBUTE_ID]);
+
+    let state: IoFToken = ft.read_state(0).expect("Can't read state");
+    assert!(state.balances.contains(&(program_id(&store), PRICE)));
+    assert!(state.balances.contains(&(program_id(&tmg), 0)));
+}
+
+#[test]
+fn failures() {
+    let sys = System::new();
+    sys.init_logger();
+    let (tmg, ft, store) = init_programs(&sys);
+
+    let res = tmg.send(
+        OWNER,
+        TmgAction::ApproveTokens {
+            account: program_id(&store),
+            amount: PRICE,
+        },
+    );
+    assert!(res.contains(&(
+        OWNER,
+        Err::<TmgReply, TmgError>(TmgError::FTokenContractNotSet).encode()
+    )));
+
+    let res = tmg.send(ADMIN, TmgAction::SetFTokenContract(program_id(&ft)));
+    assert!(res.contains(&(
+        ADMIN,
+        Err::<TmgReply, TmgError>(TmgError::NotOwner).encode()
+    )));
+
+    // Without an approval the store can't take the payment.
+    fund_tamagotchi(&ft, &tmg, PRICE);
+    let res = tmg.send(
+        OWNER,
+        TmgAction::BuyAttribute {
+            store_id: program_id(&store),
+            attribute_id: ATTRIBUTE_ID,
+        },
+    );
+    assert!(res.contains(&(
+        OWNER,
+        Err::<TmgReply, TmgError>(TmgError::Purchase).encode()
+    )));
+
+    let state = full_state(&tmg);
+    assert!(state.attributes.is_empty());
+}
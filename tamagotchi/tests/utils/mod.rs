@@ -0,0 +1,85 @@
+// Every test crate uses its own subset of the helpers,
+// the factory and battle tests include them by path.
+#![allow(dead_code)]
+
+use gstd::{ActorId, CodeId};
+use gtest::{Program, System};
+use tamagotchi_factory_io::{FactoryStateQuery, FactoryStateReply, InitFactory, TamagotchiId};
+use tamagotchi_io::*;
+
+pub const TAMAGOTCHI_WASM: &str = "../target/wasm32-unknown-unknown/debug/tamagotchi.opt.wasm";
+pub const FACTORY_WASM: &str = "../target/wasm32-unknown-unknown/debug/tamagotchi_factory.opt.wasm";
+
+pub fn program_id(program: &Program<'_>) -> ActorId {
+    program.id().into_bytes().into()
+}
+
+/// The init of a tamagotchi named "Tama" that its deployer owns.
+pub fn tmg_init(config: Option<TmgConfig>) -> TmgInit {
+    TmgInit {
+        name: String::from("Tama"),
+        config,
+        owner: None,
+        offspring: None,
+    }
+}
+
+pub fn init_tamagotchi(sys: &System, owner: u64) -> Program<'_> {
+    init_tamagotchi_with_config(sys, owner, None)
+}
+
+pub fn init_tamagotchi_with_config(
+    sys: &System,
+    owner: u64,
+    config: Option<TmgConfig>,
+) -> Program<'_> {
+    let tmg = Program::from_file(sys, TAMAGOTCHI_WASM);
+
+    let res = tmg.send(owner, tmg_init(config));
+    assert!(!res.main_failed());
+
+    tmg
+}
+
+pub fn init_factory(sys: &System, admin: u64) -> Program<'_> {
+    let factory = Program::from_file(sys, FACTORY_WASM);
+    let tamagotchi_code_id: CodeId = sys.submit_code(TAMAGOTCHI_WASM).into_bytes().into();
+
+    let res = factory.send(admin, InitFactory { tamagotchi_code_id });
+    assert!(!res.main_failed());
+
+    factory
+}
+
+pub fn tamagotchis(factory: &Program<'_>, owner: u64) -> Vec<TamagotchiId> {
+    let FactoryStateReply::Tamagotchis(tamagotchis) = factory
+        .read_state(FactoryStateQuery::Tamagotchis(owner.into()))
+        .expect("Can't read state")
+    else {
+        panic!("Unexpected state reply");
+    };
+
+    tamagotchis
+}
+
+pub fn full_state(tmg: &Program<'_>) -> Tamagotchi {
+    let TmgStateReply::Full(state) = tmg
+        .read_state(TmgStateQuery::Full)
+        .expect("Can't read state")
+    else {
+        panic!("Unexpected state reply");
+    };
+
+    state
+}
+
+pub fn mood(tmg: &Program<'_>) -> Mood {
+    let TmgStateReply::Mood(mood) = tmg
+        .read_state(TmgStateQuery::Mood)
+        .expect("Can't read state")
+    else {
+        panic!("Unexpected state reply");
+    };
+
+    mood
+}